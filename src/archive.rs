@@ -0,0 +1,397 @@
+//! An external archive of non-dominated solutions.
+//!
+//! Selection truncates the population every generation, so that good
+//! solutions found during a run can get lost. An archive keeps all
+//! non-dominated solutions ever inserted, or, if bounded, a subset of
+//! them that is pruned using the crowding distance. An epsilon archive
+//! keeps at most one solution per epsilon-box, which bounds it's size
+//! while guaranteeing convergence.
+
+use std::cmp::Ordering;
+use std::mem;
+use non_dominated_sort::{Front, SolutionWithIndex};
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use crowding_distance::{assign_crowding_distance, cmp_crowding_distance};
+use epsilon_dominance::{epsilon_box_ord, is_rejected_by, EpsilonBoxDominance};
+
+/// The outcome of inserting a candidate into an archive.
+#[derive(Debug)]
+pub struct Insertion<T> {
+    /// Whether the candidate is a member of the archive after the
+    /// insertion.
+    pub accepted: bool,
+
+    /// The members that were removed from the archive, either because
+    /// the candidate dominates them, or because they were pruned.
+    pub evicted: Vec<T>,
+}
+
+/// An archive of mutually non-dominated solutions.
+///
+/// The members are either the solutions themselves (see `insert`), or
+/// of any type the solution can be obtained from, for instance the
+/// `Individual`s of the engine (see `insert_by`).
+///
+/// A candidate is rejected if it is dominated by a member, or if a
+/// member has equal values in all objectives. Otherwise it is accepted,
+/// and the members it dominates are evicted. If the archive is bounded
+/// and exceeds it's capacity, the member with the least crowding
+/// distance is pruned, which might be the candidate itself.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ParetoArchive<T> {
+    members: Vec<T>,
+    capacity: Option<usize>,
+}
+
+impl<T> Default for ParetoArchive<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ParetoArchive<T> {
+    /// An unbounded archive.
+    pub fn new() -> Self {
+        ParetoArchive {
+            members: Vec::new(),
+            capacity: None,
+        }
+    }
+
+    /// An archive of at most `capacity` members.
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        ParetoArchive {
+            members: Vec::new(),
+            capacity: Some(capacity),
+        }
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    /// The members, in the order of their insertion.
+    pub fn members(&self) -> &[T] {
+        &self.members
+    }
+
+    pub fn into_members(self) -> Vec<T> {
+        self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Inserts `candidate`, keeping the members mutually non-dominated
+    /// according to `multi_objective`.
+    pub fn insert<M>(&mut self, candidate: T, multi_objective: &M) -> Insertion<T>
+    where
+        M: ObjectiveSet<Solution = T>,
+        M::Distance: ToF64,
+    {
+        self.insert_by(candidate, multi_objective, |member| member)
+    }
+
+    /// Like `insert`, but the objectives are evaluated on the solution
+    /// that `solution` returns for a member, e.g. `|i| &i.fitness` for
+    /// an `Individual`. Use the same `solution` for all insertions.
+    pub fn insert_by<M, K>(&mut self, candidate: T, multi_objective: &M, solution: K) -> Insertion<T>
+    where
+        M: ObjectiveSet,
+        M::Distance: ToF64,
+        K: Fn(&T) -> &M::Solution,
+    {
+        let rejected = self.members.iter().any(|member| {
+            multi_objective.domination_ord(solution(member), solution(&candidate)) == Ordering::Less
+                || equal_objectives(multi_objective, solution(member), solution(&candidate))
+        });
+        if rejected {
+            return Insertion {
+                accepted: false,
+                evicted: Vec::new(),
+            };
+        }
+
+        let mut evicted = Vec::new();
+        for member in mem::take(&mut self.members) {
+            if multi_objective.domination_ord(solution(&candidate), solution(&member)) == Ordering::Less {
+                evicted.push(member);
+            } else {
+                self.members.push(member);
+            }
+        }
+        self.members.push(candidate);
+
+        let mut accepted = true;
+        if let Some(capacity) = self.capacity {
+            if self.members.len() > capacity {
+                let index = self.most_crowded(multi_objective, &solution);
+                let pruned = self.members.remove(index);
+                if index == self.members.len() {
+                    accepted = false;
+                } else {
+                    evicted.push(pruned);
+                }
+            }
+        }
+
+        Insertion { accepted, evicted }
+    }
+
+    /// The index of the member with the least crowding distance. Of
+    /// equally crowded members, the earliest inserted.
+    fn most_crowded<M, K>(&self, multi_objective: &M, solution: &K) -> usize
+    where
+        M: ObjectiveSet,
+        M::Distance: ToF64,
+        K: Fn(&T) -> &M::Solution,
+    {
+        let front = Front {
+            rank: 0,
+            solutions: self.members
+                .iter()
+                .enumerate()
+                .map(|(index, member)| SolutionWithIndex {
+                    solution: solution(member),
+                    index,
+                })
+                .collect(),
+        };
+        let (assigned, _) = assign_crowding_distance(&front, multi_objective);
+
+        assigned
+            .iter()
+            .min_by(|a, b| {
+                cmp_crowding_distance(a.crowding_distance, b.crowding_distance)
+                    .reverse()
+                    .then(a.index.cmp(&b.index))
+            })
+            .unwrap()
+            .index
+    }
+}
+
+fn equal_objectives<M: ObjectiveSet>(multi_objective: &M, a: &M::Solution, b: &M::Solution) -> bool {
+    (0..multi_objective.num_objectives())
+        .all(|i| multi_objective.objective(i).total_order(a, b) == Ordering::Equal)
+}
+
+/// An archive of solutions which are non-dominated according to
+/// epsilon-box dominance.
+///
+/// A candidate is rejected if it's box is dominated by the box of a
+/// member, or if a member in the same box is not dominated by the
+/// candidate (see `EpsilonBoxDominance`). Otherwise it is accepted, and
+/// the members it dominates are evicted. Hence, the archive contains at
+/// most one member per box.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EpsilonArchive<T> {
+    members: Vec<T>,
+    /// The box position of each member.
+    positions: Vec<Vec<f64>>,
+}
+
+impl<T> Default for EpsilonArchive<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> EpsilonArchive<T> {
+    pub fn new() -> Self {
+        EpsilonArchive {
+            members: Vec::new(),
+            positions: Vec::new(),
+        }
+    }
+
+    /// The members, in the order of their insertion.
+    pub fn members(&self) -> &[T] {
+        &self.members
+    }
+
+    pub fn into_members(self) -> Vec<T> {
+        self.members
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// Inserts `candidate`, keeping the members mutually non-dominated
+    /// according to `dominance`.
+    pub fn insert<M>(&mut self, candidate: T, dominance: &EpsilonBoxDominance<M>) -> Insertion<T>
+    where
+        M: ObjectiveSet<Solution = T>,
+        M::Distance: ToF64,
+    {
+        self.insert_by(candidate, dominance, |member| member)
+    }
+
+    /// Like `insert`, but the objectives are evaluated on the solution
+    /// that `solution` returns for a member. Use the same `solution` for
+    /// all insertions.
+    pub fn insert_by<M, K>(&mut self, candidate: T, dominance: &EpsilonBoxDominance<M>, solution: K) -> Insertion<T>
+    where
+        M: ObjectiveSet,
+        M::Distance: ToF64,
+        K: Fn(&T) -> &M::Solution,
+    {
+        let position = dominance.box_position(solution(&candidate));
+        let mo = &dominance.multi_objective;
+
+        let rejected = self.members
+            .iter()
+            .zip(self.positions.iter())
+            .any(|(member, member_position)| {
+                let pareto = mo.domination_ord(solution(member), solution(&candidate));
+                is_rejected_by(&position, member_position, pareto)
+            });
+        if rejected {
+            return Insertion {
+                accepted: false,
+                evicted: Vec::new(),
+            };
+        }
+
+        let mut evicted = Vec::new();
+        let members = mem::take(&mut self.members);
+        let positions = mem::take(&mut self.positions);
+        for (member, member_position) in members.into_iter().zip(positions) {
+            let pareto = mo.domination_ord(solution(&candidate), solution(&member));
+            if epsilon_box_ord(&position, &member_position, pareto) == Ordering::Less {
+                evicted.push(member);
+            } else {
+                self.members.push(member);
+                self.positions.push(member_position);
+            }
+        }
+        self.members.push(candidate);
+        self.positions.push(position);
+
+        Insertion {
+            accepted: true,
+            evicted,
+        }
+    }
+}
+
+#[test]
+fn test_pareto_archive() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let mut archive = ParetoArchive::new();
+
+    let insertion = archive.insert(Tuple(2, 2), &mo);
+    assert!(insertion.accepted);
+    assert!(insertion.evicted.is_empty());
+
+    assert!(archive.insert(Tuple(1, 3), &mo).accepted);
+    assert!(archive.insert(Tuple(3, 1), &mo).accepted);
+
+    // dominated, or equal to a member
+    assert!(!archive.insert(Tuple(3, 3), &mo).accepted);
+    assert!(!archive.insert(Tuple(2, 2), &mo).accepted);
+    assert_eq!(3, archive.len());
+
+    // dominates two members
+    let insertion = archive.insert(Tuple(1, 2), &mo);
+    assert!(insertion.accepted);
+    assert_eq!(vec![Tuple(2, 2), Tuple(1, 3)], insertion.evicted);
+    assert_eq!(&[Tuple(3, 1), Tuple(1, 2)], archive.members());
+}
+
+#[test]
+fn test_bounded_pareto_archive() {
+    use objective::Objective;
+    use multi_objective::MultiObjective;
+    use engine::Individual;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+    let mut archive = ParetoArchive::bounded(3);
+
+    let individual = |x: f64| Individual {
+        genome: x,
+        fitness: vec![x, 4.0 - x],
+        rank: 0,
+        crowding_distance: 0.0,
+    };
+    fn fitness(individual: &Individual<f64, Point>) -> &Point {
+        &individual.fitness
+    }
+
+    assert!(archive.insert_by(individual(0.0), &mo, fitness).accepted);
+    assert!(archive.insert_by(individual(4.0), &mo, fitness).accepted);
+    assert!(archive.insert_by(individual(1.0), &mo, fitness).accepted);
+
+    // Either 1.0 or 3.0 is pruned. Both are equally crowded, and the
+    // earlier inserted is pruned.
+    let insertion = archive.insert_by(individual(3.0), &mo, fitness);
+    assert!(insertion.accepted);
+    assert_eq!(1, insertion.evicted.len());
+    assert_eq!(1.0, insertion.evicted[0].genome);
+
+    // 3.5 is more crowded than 3.0, the extremes are never pruned.
+    let insertion = archive.insert_by(individual(3.5), &mo, fitness);
+    assert!(!insertion.accepted);
+    assert!(insertion.evicted.is_empty());
+
+    let genomes: Vec<f64> = archive.members().iter().map(|i| i.genome).collect();
+    assert_eq!(vec![0.0, 4.0, 3.0], genomes);
+}
+
+#[test]
+fn test_epsilon_archive() {
+    use objective::Objective;
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let dominance = EpsilonBoxDominance::new(MultiObjective::new(&objectives), vec![1.0, 1.0], vec![0.0, 0.0]);
+    let mut archive = EpsilonArchive::new();
+
+    // Many non-dominated solutions on the line `x + y = 4`, but only one
+    // per box is kept. The boxes of the solutions with integral values
+    // are dominated.
+    for i in 0..401 {
+        let x = i as f64 / 100.0;
+        archive.insert(vec![x, 4.0 - x], &dominance);
+    }
+    let mut boxes: Vec<_> = archive.members()
+        .iter()
+        .map(|p| (p[0].floor() as usize, p[1].floor() as usize))
+        .collect();
+    boxes.sort();
+    assert_eq!(vec![(0, 3), (1, 2), (2, 1), (3, 0)], boxes);
+
+    // A candidate in a dominating box evicts members.
+    let insertion = archive.insert(vec![0.5, 0.5], &dominance);
+    assert!(insertion.accepted);
+    assert_eq!(4, insertion.evicted.len());
+    assert_eq!(1, archive.len());
+
+    // Within the same box, pareto dominance applies, then the one closer
+    // to the corner is kept.
+    assert!(!archive.insert(vec![0.6, 0.55], &dominance).accepted);
+    assert!(!archive.insert(vec![0.1, 0.8], &dominance).accepted);
+    let insertion = archive.insert(vec![0.1, 0.6], &dominance);
+    assert!(insertion.accepted);
+    assert_eq!(vec![vec![0.5, 0.5]], insertion.evicted);
+}
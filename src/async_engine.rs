@@ -0,0 +1,406 @@
+//! Asynchronous NSGA-II without generation barriers.
+//!
+//! A pool of worker threads keeps up to `workers` fitness evaluations
+//! in flight. Whenever evaluations complete, their results are
+//! integrated into the population using `SelectAndRank`, and new
+//! offspring are dispatched right away, so that no worker waits for the
+//! slowest evaluation of a generation.
+//!
+//! The workers call `Driver::fitness`. To evaluate in external
+//! processes, let `fitness` run the process and wait for it's result.
+//!
+//! As the order in which evaluations complete depends on their runtime,
+//! a run is not reproducible, even with a seeded random number
+//! generator.
+
+use rand::Rng;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+use engine::{breed, select_and_rank_population, Driver, EvoConfig, PopulationResult};
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use observer::Observer;
+use selection::SelectAndRank;
+use termination::{MaxEvaluations, Progress, Termination};
+
+/// Runs the asynchronous NSGA-II with `workers` evaluations in flight,
+/// and returns the final ranked population.
+///
+/// The run stops after the same number of evaluations as `engine::run`,
+/// `mu + ngen * lambda`. The `lambda` of `evo_config` is otherwise
+/// ignored. Fails like `engine::run`.
+pub fn run<R, D, S, M>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    selection: &S,
+    multi_objective: &M,
+    workers: usize,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver + Sync,
+    D::Genome: Send,
+    D::Fitness: Send,
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+{
+    let mut termination = MaxEvaluations(evo_config.mu + evo_config.ngen * evo_config.lambda);
+    run_until(
+        rng,
+        driver,
+        evo_config,
+        selection,
+        multi_objective,
+        workers,
+        &mut termination,
+        &mut (),
+    )
+}
+
+/// Like `run`, but runs until `termination` is met. The `ngen` and
+/// `lambda` of `evo_config` are ignored.
+///
+/// The initial population of `mu` random genomes is rated by the
+/// workers, too. `observer` and then `termination` are called after the
+/// initial population is created and after each integration of
+/// completed evaluations, which counts as a generation. An integration
+/// reduces the population plus all offspring rated since the last
+/// integration back to `mu` individuals using `selection`.
+///
+/// Once `termination` is met, or selection fails, no more offspring are
+/// dispatched. The evaluations still in flight are completed, but
+/// discarded. The same happens if `Driver::fitness` panics, after which
+/// the panic is resumed on the calling thread.
+#[allow(clippy::too_many_arguments)]
+pub fn run_until<R, D, S, M, T, O>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    selection: &S,
+    multi_objective: &M,
+    workers: usize,
+    termination: &mut T,
+    observer: &mut O,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver + Sync,
+    D::Genome: Send,
+    D::Fitness: Send,
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+    T: Termination<D::Genome, D::Fitness> + ?Sized,
+    O: Observer<D::Genome, D::Fitness> + ?Sized,
+{
+    let start = Instant::now();
+    let workers = workers.max(1);
+
+    let (task_sender, task_receiver) = mpsc::channel::<D::Genome>();
+    let (result_sender, result_receiver) = mpsc::channel::<thread::Result<(D::Genome, D::Fitness)>>();
+    let task_receiver = Mutex::new(task_receiver);
+
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            let task_receiver = &task_receiver;
+            let result_sender = result_sender.clone();
+            scope.spawn(move || loop {
+                // the lock is released before the evaluation
+                let task = task_receiver.lock().unwrap().recv();
+                match task {
+                    Ok(genome) => {
+                        // a panic is sent to the main thread, which
+                        // resumes it.
+                        let fitness = panic::catch_unwind(AssertUnwindSafe(|| driver.fitness(&genome)));
+                        if result_sender.send(fitness.map(|fitness| (genome, fitness))).is_err() {
+                            break;
+                        }
+                    }
+                    // no more tasks
+                    Err(_) => break,
+                }
+            });
+        }
+        // Only the workers send results, so that receiving fails once
+        // all of them have stopped.
+        drop(result_sender);
+
+        for _ in 0..evo_config.mu {
+            task_sender.send(driver.random_genome(rng)).unwrap();
+        }
+        let initial: thread::Result<Vec<_>> = result_receiver.iter().take(evo_config.mu).collect();
+        let (genomes, fitness): (Vec<_>, Vec<_>) = match initial {
+            Ok(initial) => initial.into_iter().unzip(),
+            Err(payload) => {
+                drop(task_sender);
+                panic::resume_unwind(payload);
+            }
+        };
+        let mut population = select_and_rank_population(
+            selection,
+            multi_objective,
+            genomes,
+            fitness,
+            evo_config.mu,
+            evo_config.nan_policy,
+        )?;
+
+        let mut in_flight = 0;
+
+        let mut progress = Progress::new(start, 0, evo_config.mu);
+        while !progress.is_finished(&population, multi_objective, termination, observer) {
+            while in_flight < workers {
+                task_sender.send(breed(rng, driver, evo_config, &population)).unwrap();
+                in_flight += 1;
+            }
+
+            // wait for the next result, and take all others that have
+            // completed in the meantime.
+            let first = match result_receiver.recv() {
+                Ok(result) => result,
+                // no worker is left
+                Err(_) => break,
+            };
+            let completed: thread::Result<Vec<_>> = Some(first).into_iter().chain(result_receiver.try_iter()).collect();
+            let completed = match completed {
+                Ok(completed) => completed,
+                Err(payload) => {
+                    drop(task_sender);
+                    panic::resume_unwind(payload);
+                }
+            };
+            in_flight -= completed.len();
+            let evaluations = completed.len();
+
+            let (mut genomes, mut fitness): (Vec<_>, Vec<_>) = completed.into_iter().unzip();
+            for individual in population {
+                genomes.push(individual.genome);
+                fitness.push(individual.fitness);
+            }
+            population = select_and_rank_population(
+                selection,
+                multi_objective,
+                genomes,
+                fitness,
+                evo_config.mu,
+                evo_config.nan_policy,
+            )?;
+            progress.next_generation(evaluations);
+        }
+
+        // Stop the workers. `task_sender` is moved into this closure, so
+        // that it is dropped on an early return, too.
+        drop(task_sender);
+        Ok(population)
+    })
+}
+
+#[cfg(test)]
+mod test_driver {
+    use rand::Rng;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Barrier;
+    use std::thread;
+    use std::time::Duration;
+    use engine::Driver;
+    use test_helper_objective::SquaresDriver;
+
+    /// A `SquaresDriver`, with evaluations of varying runtime. Tracks the
+    /// largest number of concurrent evaluations.
+    #[derive(Default)]
+    pub struct SlowSquaresDriver {
+        pub driver: SquaresDriver,
+        pub running: AtomicUsize,
+        pub max_running: AtomicUsize,
+        /// If set, the first two evaluations wait for each other, so
+        /// that they overlap. Needs at least two workers.
+        pub overlap: Option<Barrier>,
+        pub started: AtomicUsize,
+    }
+
+    impl Driver for SlowSquaresDriver {
+        type Genome = f64;
+        type Fitness = (f64, f64);
+
+        fn random_genome<R: Rng>(&self, rng: &mut R) -> f64 {
+            self.driver.random_genome(rng)
+        }
+
+        fn fitness(&self, x: &f64) -> (f64, f64) {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            if let Some(ref overlap) = self.overlap {
+                if self.started.fetch_add(1, Ordering::SeqCst) < 2 {
+                    overlap.wait();
+                }
+            }
+            thread::sleep(Duration::from_micros((x.abs() * 100.0) as u64 % 500));
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            self.driver.fitness(x)
+        }
+
+        fn mate<R: Rng>(&self, rng: &mut R, parent1: &f64, parent2: &f64) -> f64 {
+            self.driver.mate(rng, parent1, parent2)
+        }
+    }
+}
+
+#[test]
+fn test_run_async() {
+    use rand::{SeedableRng, XorShiftRng};
+    use std::sync::atomic::Ordering;
+    use std::sync::Barrier;
+    use select_nsga::SelectNSGA;
+    use selection::NanPolicy;
+    use vector_objective::VectorMultiObjective;
+    use self::test_driver::SlowSquaresDriver;
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let evo_config = EvoConfig {
+        mu: 20,
+        lambda: 20,
+        k: 2,
+        ngen: 50,
+        nan_policy: NanPolicy::Worst,
+    };
+    let mo = VectorMultiObjective::minimize(2);
+    let driver = SlowSquaresDriver {
+        overlap: Some(Barrier::new(2)),
+        ..SlowSquaresDriver::default()
+    };
+
+    let population = run(&mut rng, &driver, &evo_config, &SelectNSGA::default(), &mo, 4).unwrap();
+
+    // The order of the evaluations is not reproducible, allow a little
+    // slack.
+    assert_eq!(20, population.len());
+    for individual in population.iter() {
+        assert_eq!(0, individual.rank);
+        assert!(individual.genome > -0.1 && individual.genome < 2.1);
+    }
+
+    // The first two evaluations overlap.
+    let max_running = driver.max_running.load(Ordering::SeqCst);
+    assert!((2..=4).contains(&max_running));
+    assert_eq!(0, driver.running.load(Ordering::SeqCst));
+}
+
+#[test]
+fn test_run_async_until() {
+    use rand::{SeedableRng, XorShiftRng};
+    use observer::GenerationStats;
+    use select_nsga::SelectNSGA;
+    use selection::NanPolicy;
+    use vector_objective::VectorMultiObjective;
+    use self::test_driver::SlowSquaresDriver;
+
+    struct Evaluations(Vec<usize>);
+
+    impl<G, F> Observer<G, F> for Evaluations {
+        fn observe(&mut self, stats: &GenerationStats<G, F>) {
+            assert_eq!(10, stats.population.len());
+            self.0.push(stats.evaluations);
+        }
+    }
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let evo_config = EvoConfig {
+        mu: 10,
+        lambda: 0,
+        k: 2,
+        ngen: 0,
+        nan_policy: NanPolicy::Worst,
+    };
+    let mo = VectorMultiObjective::minimize(2);
+
+    let mut evaluations = Evaluations(Vec::new());
+    run_until(
+        &mut rng,
+        &SlowSquaresDriver::default(),
+        &evo_config,
+        &SelectNSGA::default(),
+        &mo,
+        3,
+        &mut MaxEvaluations(50),
+        &mut evaluations,
+    )
+    .unwrap();
+
+    // Each integration adds at least one evaluation.
+    assert_eq!(10, evaluations.0[0]);
+    assert!(evaluations.0.windows(2).all(|w| w[0] < w[1]));
+    let last = *evaluations.0.last().unwrap();
+    assert!((50..50 + 3).contains(&last));
+}
+
+#[test]
+fn test_run_async_panic() {
+    use rand::{SeedableRng, XorShiftRng};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use select_nsga::SelectNSGA;
+    use selection::NanPolicy;
+    use vector_objective::VectorMultiObjective;
+    use self::test_driver::SlowSquaresDriver;
+
+    // Panics in the given evaluation.
+    struct PanickingDriver {
+        driver: SlowSquaresDriver,
+        evaluations: AtomicUsize,
+        panic_at: usize,
+    }
+
+    impl Driver for PanickingDriver {
+        type Genome = f64;
+        type Fitness = (f64, f64);
+
+        fn random_genome<R: Rng>(&self, rng: &mut R) -> f64 {
+            self.driver.random_genome(rng)
+        }
+
+        fn fitness(&self, x: &f64) -> (f64, f64) {
+            if self.evaluations.fetch_add(1, Ordering::SeqCst) == self.panic_at {
+                panic!("evaluation failed");
+            }
+            self.driver.fitness(x)
+        }
+
+        fn mate<R: Rng>(&self, rng: &mut R, parent1: &f64, parent2: &f64) -> f64 {
+            self.driver.mate(rng, parent1, parent2)
+        }
+    }
+
+    let evo_config = EvoConfig {
+        mu: 10,
+        lambda: 10,
+        k: 2,
+        ngen: 20,
+        nan_policy: NanPolicy::Worst,
+    };
+    let mo = VectorMultiObjective::minimize(2);
+
+    // in the initial population, and later on
+    for &panic_at in [3, 50].iter() {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let driver = PanickingDriver {
+            driver: SlowSquaresDriver::default(),
+            evaluations: AtomicUsize::new(0),
+            panic_at,
+        };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            run(&mut rng, &driver, &evo_config, &SelectNSGA::default(), &mo, 4)
+        }));
+        let payload = result.err().unwrap();
+        assert_eq!(Some(&"evaluation failed"), payload.downcast_ref::<&str>());
+
+        // The run stops early, and the evaluations in flight are
+        // completed.
+        assert!(driver.evaluations.load(Ordering::SeqCst) < 10 + 20 * 10);
+        assert_eq!(0, driver.driver.running.load(Ordering::SeqCst));
+    }
+}
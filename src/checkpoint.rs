@@ -0,0 +1,262 @@
+//! Checkpoint and resume of evolution runs.
+//!
+//! A `Checkpoint` captures everything needed to continue a run: the
+//! configuration, the number of generations and evaluations performed,
+//! the ranked population and the state of the random number generator.
+//! With the `serde` feature enabled, it can be serialized, and a resumed
+//! run continues bit-identically to an uninterrupted run, with
+//! `Checkpoint::run` as well as with `Checkpoint::run_until`.
+//!
+//! The random number generators of `rand` cannot be serialized. Use
+//! `ResumableRng`, or any other serializable `Rng`. Note that the
+//! crowding distance of boundary solutions is infinite, which some
+//! formats (e.g. JSON) cannot represent.
+
+use std::mem;
+use std::num::Wrapping;
+use std::time::Instant;
+use rand::{Rng, SeedableRng};
+use engine::{generational_step, initial_population, Driver, EvoConfig, Individual};
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use selection::{SelectAndRank, SelectionError};
+use termination::{MaxGenerations, Progress, Termination};
+
+/// The xorshift generator of `rand::XorShiftRng`, but with accessible
+/// state. Both generate the same numbers for the same seed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ResumableRng {
+    pub state: [u32; 4],
+}
+
+impl Rng for ResumableRng {
+    fn next_u32(&mut self) -> u32 {
+        let [x, y, z, w] = self.state;
+        let x = Wrapping(x);
+        let t = x ^ (x << 11);
+        let w = Wrapping(w);
+        let w_new = w ^ (w >> 19) ^ (t ^ (t >> 8));
+        self.state = [y, z, w.0, w_new.0];
+        w_new.0
+    }
+}
+
+impl SeedableRng<[u32; 4]> for ResumableRng {
+    /// Panics if `seed` is entirely 0.
+    fn reseed(&mut self, seed: [u32; 4]) {
+        assert!(!seed.iter().all(|&x| x == 0));
+        self.state = seed;
+    }
+
+    /// Panics if `seed` is entirely 0.
+    fn from_seed(seed: [u32; 4]) -> ResumableRng {
+        assert!(!seed.iter().all(|&x| x == 0));
+        ResumableRng { state: seed }
+    }
+}
+
+/// The state of an evolution run.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Checkpoint<G, F, R> {
+    pub evo_config: EvoConfig,
+    /// The number of generations performed so far.
+    pub generation: usize,
+    /// The number of fitness evaluations performed so far.
+    pub evaluations: usize,
+    /// The ranked population, including the fitness, rank and crowding
+    /// distance of each individual.
+    pub population: Vec<Individual<G, F>>,
+    pub rng: R,
+}
+
+impl<G, F, R: Rng> Checkpoint<G, F, R> {
+    /// Starts a new run by generating the initial population. Fails like
+    /// `engine::initial_population`.
+    pub fn start<D, S, M>(
+        mut rng: R,
+        driver: &D,
+        evo_config: &EvoConfig,
+        selection: &S,
+        multi_objective: &M,
+    ) -> Result<Self, SelectionError>
+    where
+        D: Driver<Genome = G, Fitness = F>,
+        S: SelectAndRank,
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let population = initial_population(&mut rng, driver, evo_config, selection, multi_objective)?;
+        Ok(Checkpoint {
+            evo_config: evo_config.clone(),
+            generation: 0,
+            evaluations: evo_config.mu,
+            population,
+            rng,
+        })
+    }
+
+    /// Whether all `ngen` generations have been performed.
+    pub fn is_finished(&self) -> bool {
+        self.generation >= self.evo_config.ngen
+    }
+
+    /// Performs one generation. Fails like `engine::generational_step`,
+    /// which leaves the checkpoint with an empty population. Resume from
+    /// a previously persisted checkpoint in that case.
+    pub fn step<D, S, M>(&mut self, driver: &D, selection: &S, multi_objective: &M) -> Result<(), SelectionError>
+    where
+        D: Driver<Genome = G, Fitness = F>,
+        S: SelectAndRank,
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let population = mem::take(&mut self.population);
+        self.population = generational_step(
+            &mut self.rng,
+            driver,
+            &self.evo_config,
+            selection,
+            multi_objective,
+            population,
+        )?;
+        self.generation += 1;
+        self.evaluations += self.evo_config.lambda;
+        Ok(())
+    }
+
+    /// Performs the remaining generations. `on_generation` is called
+    /// after each generation, e.g. to persist the checkpoint.
+    pub fn run<D, S, M, C>(
+        self,
+        driver: &D,
+        selection: &S,
+        multi_objective: &M,
+        on_generation: C,
+    ) -> Result<Self, SelectionError>
+    where
+        D: Driver<Genome = G, Fitness = F>,
+        S: SelectAndRank,
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+        C: FnMut(&Self),
+    {
+        let mut termination = MaxGenerations(self.evo_config.ngen);
+        self.run_until(driver, selection, multi_objective, &mut termination, on_generation)
+    }
+
+    /// Performs generations until `termination` is met, like
+    /// `engine::run_until`. The `ngen` of `evo_config` is ignored.
+    /// `on_generation` is called after each generation, e.g. to persist
+    /// the checkpoint.
+    ///
+    /// `termination` is called with the generation and evaluations of
+    /// the whole run, so that `MaxGenerations` and `MaxEvaluations`
+    /// continue where the run was interrupted. The times, however, are
+    /// measured from the call to `run_until`, and stateful conditions
+    /// like `HypervolumeStagnation` start over.
+    pub fn run_until<D, S, M, T, C>(
+        mut self,
+        driver: &D,
+        selection: &S,
+        multi_objective: &M,
+        termination: &mut T,
+        mut on_generation: C,
+    ) -> Result<Self, SelectionError>
+    where
+        D: Driver<Genome = G, Fitness = F>,
+        S: SelectAndRank,
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+        T: Termination<G, F> + ?Sized,
+        C: FnMut(&Self),
+    {
+        let mut progress = Progress::new(Instant::now(), self.generation, self.evaluations);
+        while !progress.is_finished(&self.population, multi_objective, termination, &mut ()) {
+            self.step(driver, selection, multi_objective)?;
+            progress.next_generation(self.evo_config.lambda);
+            on_generation(&self);
+        }
+        Ok(self)
+    }
+}
+
+#[test]
+fn test_resumable_rng() {
+    use rand::XorShiftRng;
+
+    let mut a = ResumableRng::from_seed([1, 2, 3, 4]);
+    let mut b = XorShiftRng::from_seed([1, 2, 3, 4]);
+    for _ in 0..100 {
+        assert_eq!(b.next_u32(), a.next_u32());
+    }
+    assert_eq!(b.gen_range(0.0, 1.0), a.gen_range(0.0, 1.0));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_checkpoint_resume() {
+    use bincode;
+    use engine::{run, run_until};
+    use termination::MaxEvaluations;
+    use select_nsga::SelectNSGA;
+    use selection::NanPolicy;
+    use test_helper_objective::SquaresDriver;
+    use vector_objective::VectorMultiObjective;
+
+    let driver = SquaresDriver::default();
+    let mo = VectorMultiObjective::minimize(2);
+    let selection = SelectNSGA::default();
+    let evo_config = EvoConfig {
+        mu: 20,
+        lambda: 20,
+        k: 2,
+        ngen: 10,
+        nan_policy: NanPolicy::Worst,
+    };
+
+    let mut rng = ResumableRng::from_seed([1, 2, 3, 4]);
+    let uninterrupted = run(&mut rng, &driver, &evo_config, &selection, &mo).unwrap();
+
+    // Persist after each generation, and resume from generation 4.
+    let mut saved = Vec::new();
+    let rng = ResumableRng::from_seed([1, 2, 3, 4]);
+    Checkpoint::start(rng, &driver, &evo_config, &selection, &mo)
+        .unwrap()
+        .run(&driver, &selection, &mo, |checkpoint| {
+            saved.push(bincode::serialize(checkpoint).unwrap())
+        })
+        .unwrap();
+    assert_eq!(10, saved.len());
+
+    let checkpoint: Checkpoint<f64, (f64, f64), ResumableRng> = bincode::deserialize(&saved[3]).unwrap();
+    assert_eq!(4, checkpoint.generation);
+    let resumed = checkpoint.run(&driver, &selection, &mo, |_| {}).unwrap();
+
+    assert_eq!(10, resumed.generation);
+    assert_eq!(20 + 10 * 20, resumed.evaluations);
+    assert_same(&uninterrupted, &resumed.population);
+
+    // Resume with an evaluation budget.
+    let mut rng = ResumableRng::from_seed([1, 2, 3, 4]);
+    let uninterrupted = run_until(&mut rng, &driver, &evo_config, &selection, &mo, &mut MaxEvaluations(250), &mut ()).unwrap();
+
+    let checkpoint: Checkpoint<f64, (f64, f64), ResumableRng> = bincode::deserialize(&saved[3]).unwrap();
+    assert_eq!(20 + 4 * 20, checkpoint.evaluations);
+    let resumed = checkpoint.run_until(&driver, &selection, &mo, &mut MaxEvaluations(250), |_| {}).unwrap();
+
+    assert_eq!(12, resumed.generation);
+    assert_eq!(260, resumed.evaluations);
+    assert_same(&uninterrupted, &resumed.population);
+
+    fn assert_same(a: &[Individual<f64, (f64, f64)>], b: &[Individual<f64, (f64, f64)>]) {
+        assert_eq!(a.len(), b.len());
+        for (a, b) in a.iter().zip(b.iter()) {
+            assert_eq!(a.genome.to_bits(), b.genome.to_bits());
+            assert_eq!(a.fitness, b.fitness);
+            assert_eq!(a.rank, b.rank);
+            assert_eq!(a.crowding_distance.to_bits(), b.crowding_distance.to_bits());
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::cmp::Ordering;
+use objective::{nan_worst_cmp, Objective};
+use constraint::Constraint;
+use multi_objective::{MultiObjective, ObjectiveSet};
+use non_dominated_sort::DominationOrd;
+
+/// Combines a `MultiObjective` with a list of constraints, using the
+/// constrained-domination rule of Deb:
+///
+/// - A feasible solution dominates any infeasible solution.
+///
+/// - Of two infeasible solutions, the one with the smaller total
+///   constraint violation dominates the other.
+///
+/// - Of two feasible solutions, the usual pareto dominance of the
+///   objectives applies.
+///
+/// The total constraint violation is the sum of the positive violations
+/// of all constraints. Negative values (slack) of a satisfied constraint
+/// count as zero, so that they cannot cancel the violation of another
+/// constraint.
+pub struct ConstrainedMultiObjective<'a, S, D>
+where
+    S: 'a,
+    D: 'a,
+{
+    pub multi_objective: MultiObjective<'a, S, D>,
+    pub constraints: &'a [&'a dyn Constraint<Solution = S>],
+}
+
+impl<'a, S, D> ConstrainedMultiObjective<'a, S, D>
+where
+    S: 'a,
+    D: 'a,
+{
+    pub fn new(
+        multi_objective: MultiObjective<'a, S, D>,
+        constraints: &'a [&'a dyn Constraint<Solution = S>],
+    ) -> Self {
+        Self {
+            multi_objective,
+            constraints,
+        }
+    }
+
+    /// The sum of the positive violations of all constraints. NaN if
+    /// any violation is NaN.
+    pub fn total_violation(&self, solution: &S) -> f64 {
+        self.constraints
+            .iter()
+            .map(|constraint| constraint.violation(solution))
+            // unlike `f64::max`, keeps NaN
+            .map(|violation| if violation < 0.0 { 0.0 } else { violation })
+            .sum()
+    }
+
+    /// Whether the total violation is not positive. A NaN violation is
+    /// infeasible.
+    pub fn is_feasible(&self, solution: &S) -> bool {
+        self.total_violation(solution) <= 0.0
+    }
+}
+
+impl<'a, S, D> ObjectiveSet for ConstrainedMultiObjective<'a, S, D>
+where
+    S: 'a,
+    D: 'a,
+{
+    type Distance = D;
+
+    fn num_objectives(&self) -> usize {
+        self.multi_objective.num_objectives()
+    }
+
+    fn objective(&self, i: usize) -> &dyn Objective<Solution = S, Distance = D> {
+        self.multi_objective.objective(i)
+    }
+}
+
+impl<'a, S, D> DominationOrd for ConstrainedMultiObjective<'a, S, D>
+where
+    S: 'a,
+    D: 'a,
+{
+    type Solution = S;
+
+    fn domination_ord(&self, a: &Self::Solution, b: &Self::Solution) -> Ordering {
+        let violation_a = self.total_violation(a);
+        let violation_b = self.total_violation(b);
+
+        // a NaN violation counts as infeasible
+        let infeasible = |violation: f64| violation.is_nan() || violation > 0.0;
+        match (infeasible(violation_a), infeasible(violation_b)) {
+            // both feasible
+            (false, false) => self.multi_objective.domination_ord(a, b),
+            // only `a` is feasible
+            (false, true) => Ordering::Less,
+            // only `b` is feasible
+            (true, false) => Ordering::Greater,
+            // both infeasible
+            (true, true) => nan_worst_cmp(violation_a, violation_b),
+        }
+    }
+}
+
+#[test]
+fn test_constrained_multi_objective() {
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    // The sum of the tuple values must not exceed 4.
+    struct MaxSum;
+
+    impl Constraint for MaxSum {
+        type Solution = Tuple;
+
+        fn violation(&self, solution: &Tuple) -> f64 {
+            (solution.0 + solution.1).saturating_sub(4) as f64
+        }
+    }
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let cmo = ConstrainedMultiObjective::new(mo, &[&MaxSum]);
+
+    let a = Tuple(1, 2);
+    let b = Tuple(2, 1);
+    let c = Tuple(1, 3);
+    let d = Tuple(0, 5); // infeasible, violation 1
+    let e = Tuple(3, 4); // infeasible, violation 3
+
+    assert!(cmo.is_feasible(&a));
+    assert!(!cmo.is_feasible(&d));
+    assert_eq!(3.0, cmo.total_violation(&e));
+
+    // both feasible: pareto dominance
+    assert_eq!(Ordering::Equal, cmo.domination_ord(&a, &b));
+    assert_eq!(Ordering::Less, cmo.domination_ord(&a, &c));
+
+    // feasible beats infeasible, even if the infeasible solution is
+    // better in an objective.
+    assert_eq!(Ordering::Less, cmo.domination_ord(&c, &d));
+    assert_eq!(Ordering::Greater, cmo.domination_ord(&d, &c));
+
+    // both infeasible: less violation is better
+    assert_eq!(Ordering::Less, cmo.domination_ord(&d, &e));
+    assert_eq!(Ordering::Equal, cmo.domination_ord(&e, &e));
+}
+
+#[test]
+fn test_negative_violation() {
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    // `g(x) = x - 2 <= 0`, with the slack as a negative violation.
+    struct MaxFirst;
+
+    impl Constraint for MaxFirst {
+        type Solution = Tuple;
+
+        fn violation(&self, solution: &Tuple) -> f64 {
+            solution.0 as f64 - 2.0
+        }
+    }
+
+    // violated by 1 unless the second value is 1
+    struct SecondIsOne;
+
+    impl Constraint for SecondIsOne {
+        type Solution = Tuple;
+
+        fn violation(&self, solution: &Tuple) -> f64 {
+            if solution.1 == 1 {
+                0.0
+            } else {
+                1.0
+            }
+        }
+    }
+
+    struct Undefined;
+
+    impl Constraint for Undefined {
+        type Solution = Tuple;
+
+        fn violation(&self, _solution: &Tuple) -> f64 {
+            f64::NAN
+        }
+    }
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let cmo = ConstrainedMultiObjective::new(mo, &[&MaxFirst, &SecondIsOne]);
+
+    // The slack of 2 of the first constraint does not cancel the
+    // violation of the second.
+    assert_eq!(1.0, cmo.total_violation(&Tuple(0, 0)));
+    assert!(!cmo.is_feasible(&Tuple(0, 0)));
+    assert!(cmo.is_feasible(&Tuple(0, 1)));
+    assert_eq!(2.0, cmo.total_violation(&Tuple(3, 0)));
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let cmo = ConstrainedMultiObjective::new(mo, &[&MaxFirst, &Undefined]);
+    assert!(cmo.total_violation(&Tuple(0, 1)).is_nan());
+    assert!(!cmo.is_feasible(&Tuple(0, 1)));
+}
+
+#[test]
+fn test_constrained_select_nsga() {
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+    use selection::SelectAndRank;
+    use select_nsga::SelectNSGA;
+
+    struct MaxFirst;
+
+    impl Constraint for MaxFirst {
+        type Solution = Tuple;
+
+        fn violation(&self, solution: &Tuple) -> f64 {
+            solution.0.saturating_sub(2) as f64
+        }
+    }
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let cmo = ConstrainedMultiObjective::new(mo, &[&MaxFirst]);
+
+    // Tuple(3, 0) and Tuple(4, 0) would be pareto optimal without the
+    // constraint.
+    let solutions = vec![Tuple(3, 0), Tuple(4, 0), Tuple(2, 2), Tuple(1, 3), Tuple(2, 3)];
+
+    let selected = SelectNSGA::default().select_and_rank(&solutions, 3, &cmo);
+    assert_eq!(3, selected.len());
+
+    let mut indices: Vec<_> = selected.iter().map(|s| (s.index, s.rank)).collect();
+    indices.sort();
+    assert_eq!(vec![(2, 0), (3, 0), (4, 1)], indices);
+}
@@ -0,0 +1,17 @@
+/// A *constraint* restricts the set of feasible solutions. Given a
+/// solution, a constraint answers the question "by how much does the
+/// solution violate the constraint".
+///
+/// A solution is *feasible* if it does not violate any of it's
+/// constraints.
+pub trait Constraint {
+    /// The solution value type that we define the constraint on.
+    type Solution;
+
+    /// Returns the amount by which `solution` violates the constraint.
+    ///
+    /// The violation is zero, if the constraint is satisfied, and
+    /// positive otherwise. Larger values mean "more violated". Negative
+    /// values (e.g. the slack of `g(x) <= 0`) are treated as zero.
+    fn violation(&self, solution: &Self::Solution) -> f64;
+}
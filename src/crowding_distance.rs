@@ -1,6 +1,7 @@
-use multi_objective::MultiObjective;
+use std::cmp::Ordering;
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
 use non_dominated_sort::Front;
-use std::f64::INFINITY;
 
 pub struct AssignedCrowdingDistance<'a, S>
 where
@@ -12,15 +13,37 @@ where
     pub crowding_distance: f64,
 }
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct ObjectiveStat {
     pub spread: f64,
 }
 
+/// Compares two crowding distances, of which the higher is better and
+/// ordered first. NaN is treated as the worst crowding distance.
+pub fn cmp_crowding_distance(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (false, false) => b.partial_cmp(&a).unwrap(),
+        (false, true) => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        (true, true) => Ordering::Equal,
+    }
+}
+
 /// Assigns a crowding distance to each solution in `front`.
-pub fn assign_crowding_distance<'a, S>(
+///
+/// The distances of the objectives can be of any type implementing
+/// `ToF64`, e.g. `f32`, `i32` or `i64`. The crowding distance itself is
+/// a `f64`.
+///
+/// The spread of each objective is 0 for an empty front.
+pub fn assign_crowding_distance<'a, S, M>(
     front: &Front<'a, S>,
-    multi_objective: &MultiObjective<S, f64>,
-) -> (Vec<AssignedCrowdingDistance<'a, S>>, Vec<ObjectiveStat>) {
+    multi_objective: &M,
+) -> (Vec<AssignedCrowdingDistance<'a, S>>, Vec<ObjectiveStat>)
+where
+    M: ObjectiveSet<Solution = S>,
+    M::Distance: ToF64,
+{
     let mut a: Vec<_> = front
         .solutions
         .iter()
@@ -32,28 +55,38 @@ pub fn assign_crowding_distance<'a, S>(
         })
         .collect();
 
-    let objective_stat: Vec<_> = multi_objective
-        .objectives
-        .iter()
-        .map(|objective| {
+    let num_objectives = multi_objective.num_objectives();
+
+    if a.is_empty() {
+        let objective_stat = (0..num_objectives)
+            .map(|_| ObjectiveStat { spread: 0.0 })
+            .collect();
+        return (a, objective_stat);
+    }
+
+    let objective_stat: Vec<_> = (0..num_objectives)
+        .map(|i| {
+            let objective = multi_objective.objective(i);
+
             // First, sort according to objective
             a.sort_by(|a, b| objective.total_order(a.solution, b.solution));
 
             // Assign infinite crowding distance to the extremes
             {
-                a.first_mut().unwrap().crowding_distance = INFINITY;
-                a.last_mut().unwrap().crowding_distance = INFINITY;
+                a.first_mut().unwrap().crowding_distance = f64::INFINITY;
+                a.last_mut().unwrap().crowding_distance = f64::INFINITY;
             }
 
             // The distance between the "best" and "worst" solution
             // according to "objective".
             let spread = objective
                 .distance(a.first().unwrap().solution, a.last().unwrap().solution)
-                .abs();
-            debug_assert!(spread >= 0.0);
+                .to_f64();
+            let spread = spread.abs();
+            debug_assert!(spread >= 0.0 || spread.is_nan());
 
-            if spread > 0.0 {
-                let norm = 1.0 / (spread * (multi_objective.objectives.len() as f64));
+            if spread > 0.0 && spread.is_finite() {
+                let norm = 1.0 / (spread * (num_objectives as f64));
                 debug_assert!(norm > 0.0);
 
                 for i in 1..a.len() - 1 {
@@ -61,8 +94,9 @@ pub fn assign_crowding_distance<'a, S>(
 
                     let distance = objective
                         .distance(a[i + 1].solution, a[i - 1].solution)
-                        .abs();
-                    debug_assert!(distance >= 0.0);
+                        .to_f64();
+                    let distance = distance.abs();
+                    debug_assert!(distance >= 0.0 || distance.is_nan());
                     a[i].crowding_distance += distance * norm;
                 }
             }
@@ -71,12 +105,13 @@ pub fn assign_crowding_distance<'a, S>(
         })
         .collect();
 
-    return (a, objective_stat);
+    (a, objective_stat)
 }
 
 #[test]
 fn test_crowding_distance() {
     use test_helper_objective::{Objective1, Objective2, Tuple};
+    use multi_objective::MultiObjective;
     use non_dominated_sort::NonDominatedSort;
 
     // construct a multi objective over a Tuple
@@ -118,11 +153,161 @@ fn test_crowding_distance() {
     let cb = crowding.iter().find(|i| i.solution.eq(&b)).unwrap();
     let cd = crowding.iter().find(|i| i.solution.eq(&d)).unwrap();
 
-    assert_eq!(INFINITY, ca.crowding_distance);
-    assert_eq!(INFINITY, cb.crowding_distance);
+    assert_eq!(f64::INFINITY, ca.crowding_distance);
+    assert_eq!(f64::INFINITY, cb.crowding_distance);
 
     // only cd is in the middle. spread is in both dimensions the same
     // (2.0). norm is 1.0 / (spread * #objectives) = 1.0 / 4.0. As we
     // add two times 0.5, the crowding distance should be 1.0.
     assert_eq!(1.0, cd.crowding_distance);
 }
+
+#[test]
+fn test_crowding_distance_mixed_directions() {
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+    use multi_objective::MultiObjective;
+    use non_dominated_sort::NonDominatedSort;
+    use objective::{Directed, Objective};
+
+    // minimize the first, maximize the second value
+    let max2 = Directed::maximize(Objective2);
+    let objectives: [&dyn Objective<Solution = Tuple, Distance = f64>; 2] = [&Objective1, &max2];
+    let mo = MultiObjective::new(&objectives);
+
+    let a = Tuple(1, 1);
+    let b = Tuple(3, 3);
+    let c = Tuple(3, 1);
+    let d = Tuple(2, 2);
+
+    let solutions = vec![a, b, c, d];
+    let fronts = NonDominatedSort::new(&solutions, &mo).pareto_fronts();
+    assert_eq!(2, fronts.len());
+    assert_eq!(3, fronts[0].solutions.len());
+    assert_eq!(&c, fronts[1].solutions[0].solution);
+
+    let (crowding, stat) = assign_crowding_distance(&fronts[0], &mo);
+    assert_eq!(2.0, stat[0].spread);
+    assert_eq!(2.0, stat[1].spread);
+
+    let ca = crowding.iter().find(|i| i.solution.eq(&a)).unwrap();
+    let cb = crowding.iter().find(|i| i.solution.eq(&b)).unwrap();
+    let cd = crowding.iter().find(|i| i.solution.eq(&d)).unwrap();
+    assert_eq!(f64::INFINITY, ca.crowding_distance);
+    assert_eq!(f64::INFINITY, cb.crowding_distance);
+    assert_eq!(1.0, cd.crowding_distance);
+}
+
+#[test]
+fn test_crowding_distance_generic_distance() {
+    use std::cmp::Ordering;
+    use multi_objective::MultiObjective;
+    use objective::Objective;
+    use select_nsga::SelectNSGA;
+    use selection::SelectAndRank;
+    use non_dominated_sort::NonDominatedSort;
+
+    // A fitness of f32 values with f32 distances, and ones of i32 and
+    // i64 values with distances of the same type.
+    struct F32Coordinate(usize);
+
+    impl Objective for F32Coordinate {
+        type Solution = [f32; 2];
+        type Distance = f32;
+
+        fn total_order(&self, a: &[f32; 2], b: &[f32; 2]) -> Ordering {
+            a[self.0].partial_cmp(&b[self.0]).unwrap()
+        }
+
+        fn distance(&self, a: &[f32; 2], b: &[f32; 2]) -> f32 {
+            a[self.0] - b[self.0]
+        }
+    }
+
+    struct I32Coordinate(usize);
+
+    impl Objective for I32Coordinate {
+        type Solution = [i32; 2];
+        type Distance = i32;
+
+        fn total_order(&self, a: &[i32; 2], b: &[i32; 2]) -> Ordering {
+            a[self.0].cmp(&b[self.0])
+        }
+
+        fn distance(&self, a: &[i32; 2], b: &[i32; 2]) -> i32 {
+            a[self.0] - b[self.0]
+        }
+    }
+
+    struct I64Coordinate(usize);
+
+    impl Objective for I64Coordinate {
+        type Solution = [i64; 2];
+        type Distance = i64;
+
+        fn total_order(&self, a: &[i64; 2], b: &[i64; 2]) -> Ordering {
+            a[self.0].cmp(&b[self.0])
+        }
+
+        fn distance(&self, a: &[i64; 2], b: &[i64; 2]) -> i64 {
+            a[self.0] - b[self.0]
+        }
+    }
+
+    let mo = MultiObjective::<[f32; 2], f32>::new(&[&F32Coordinate(0), &F32Coordinate(1)]);
+    let solutions = vec![[1.0, 3.0], [3.0, 1.0], [3.0, 3.0], [2.0, 2.0]];
+    let fronts = NonDominatedSort::new(&solutions, &mo).pareto_fronts();
+    let (crowding, stat) = assign_crowding_distance(&fronts[0], &mo);
+    assert_eq!(2.0, stat[0].spread);
+    let cd = crowding.iter().find(|i| i.index == 3).unwrap();
+    assert_eq!(1.0, cd.crowding_distance);
+
+    let mo = MultiObjective::<[i32; 2], i32>::new(&[&I32Coordinate(0), &I32Coordinate(1)]);
+    let solutions = vec![[1, 3], [3, 1], [3, 3], [2, 2]];
+    let mut selected: Vec<_> = SelectNSGA::default()
+        .select_and_rank(&solutions, 3, &mo)
+        .iter()
+        .map(|s| (s.index, s.crowding_distance))
+        .collect();
+    selected.sort_by_key(|s| s.0);
+    assert_eq!(vec![(0, f64::INFINITY), (1, f64::INFINITY), (3, 1.0)], selected);
+
+    // values beyond the range of i32
+    let mo = MultiObjective::<[i64; 2], i64>::new(&[&I64Coordinate(0), &I64Coordinate(1)]);
+    let e = 1 << 40;
+    let solutions = vec![[e, 3 * e], [3 * e, e], [3 * e, 3 * e], [2 * e, 2 * e]];
+    let mut selected: Vec<_> = SelectNSGA::default()
+        .select_and_rank(&solutions, 3, &mo)
+        .iter()
+        .map(|s| (s.index, s.crowding_distance))
+        .collect();
+    selected.sort_by_key(|s| s.0);
+    assert_eq!(vec![(0, f64::INFINITY), (1, f64::INFINITY), (3, 1.0)], selected);
+}
+
+#[test]
+fn test_degenerate_fronts() {
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+    use multi_objective::MultiObjective;
+    use non_dominated_sort::SolutionWithIndex;
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+
+    let empty = Front { rank: 0, solutions: vec![] };
+    let (crowding, stat) = assign_crowding_distance(&empty, &mo);
+    assert!(crowding.is_empty());
+    assert_eq!(vec![ObjectiveStat { spread: 0.0 }, ObjectiveStat { spread: 0.0 }], stat);
+
+    let solution = Tuple(1, 2);
+    let single = Front {
+        rank: 0,
+        solutions: vec![SolutionWithIndex { solution: &solution, index: 0 }],
+    };
+    let (crowding, _) = assign_crowding_distance(&single, &mo);
+    assert_eq!(f64::INFINITY, crowding[0].crowding_distance);
+
+    // higher first, NaN last
+    let mut distances = [1.0, f64::NAN, f64::INFINITY, 2.0];
+    distances.sort_by(|&a, &b| cmp_crowding_distance(a, b));
+    assert_eq!(&[f64::INFINITY, 2.0, 1.0], &distances[..3]);
+    assert!(distances[3].is_nan());
+}
@@ -0,0 +1,707 @@
+use rand::Rng;
+use std::cmp::Ordering;
+use std::time::Instant;
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use crowding_distance::cmp_crowding_distance;
+use selection::{NanPolicy, SelectAndRank, SelectionError};
+use observer::Observer;
+use termination::{MaxGenerations, Progress, Termination};
+use tournament_selection::tournament_selection_fast;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// A *driver* defines the problem specific parts of an evolutionary
+/// run: how to create random genomes, how to rate them and how to
+/// derive new genomes from existing ones.
+///
+/// The objectives are defined on the `Fitness` value of a genome, not
+/// on the genome itself.
+///
+/// Genomes are rated one after the other, unless the driver overrides
+/// `rate`, e.g. with `rate_parallel`.
+pub trait Driver {
+    /// The genome type that we evolve.
+    type Genome;
+
+    /// The fitness value of a genome.
+    type Fitness;
+
+    /// Creates a new random genome. Used to generate the initial
+    /// population.
+    fn random_genome<R: Rng>(&self, rng: &mut R) -> Self::Genome;
+
+    /// Rates a genome (calculates it's fitness).
+    fn fitness(&self, genome: &Self::Genome) -> Self::Fitness;
+
+    /// Rates `genomes` and returns their fitness values in the same
+    /// order. The default rates them one after the other.
+    fn rate(&self, genomes: &[Self::Genome]) -> Vec<Self::Fitness> {
+        genomes.iter().map(|g| self.fitness(g)).collect()
+    }
+
+    /// Creates an offspring genome from two parents.
+    fn mate<R: Rng>(
+        &self,
+        rng: &mut R,
+        parent1: &Self::Genome,
+        parent2: &Self::Genome,
+    ) -> Self::Genome;
+
+    /// Mutates an offspring genome in place. The default is to not
+    /// mutate at all.
+    fn mutate<R: Rng>(&self, _rng: &mut R, _genome: &mut Self::Genome) {}
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EvoConfig {
+    /// size of population
+    pub mu: usize,
+    /// size of offspring population
+    pub lambda: usize,
+    /// tournament size
+    pub k: usize,
+    /// max number of generations
+    pub ngen: usize,
+    /// how to treat individuals with invalid (e.g. NaN) fitness values
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nan_policy: NanPolicy,
+}
+
+/// A rated individual together with the rank and crowding distance
+/// assigned to it by the last selection.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Individual<G, F> {
+    pub genome: G,
+    pub fitness: F,
+    pub rank: usize,
+    pub crowding_distance: f64,
+}
+
+impl<G, F> Individual<G, F> {
+    /// The crowded-comparison operator. A lower rank is better. Within
+    /// the same rank, the higher crowding distance is better.
+    pub fn crowded_cmp(&self, other: &Self) -> Ordering {
+        self.rank
+            .cmp(&other.rank)
+            .then_with(|| cmp_crowding_distance(self.crowding_distance, other.crowding_distance))
+    }
+}
+
+/// A ranked population, or why selection failed.
+pub type PopulationResult<G, F> = Result<Vec<Individual<G, F>>, SelectionError>;
+
+/// Rates `genomes` in parallel. The fitness values are returned in the
+/// order of `genomes`, so that the result does not depend on the number
+/// of threads.
+///
+/// To rate in parallel within the engine, override `Driver::rate` with
+/// this function.
+#[cfg(feature = "rayon")]
+pub fn rate_parallel<D>(driver: &D, genomes: &[D::Genome]) -> Vec<D::Fitness>
+where
+    D: Driver + Sync,
+    D::Genome: Sync,
+    D::Fitness: Send,
+{
+    genomes.par_iter().map(|g| driver.fitness(g)).collect()
+}
+
+/// Reduces the rated `genomes` to `n` individuals using `selection`
+/// and assigns rank and crowding distance to them. Individuals with
+/// invalid (e.g. NaN) fitness values are treated according to
+/// `nan_policy`.
+pub(crate) fn select_and_rank_population<G, F, S, M>(
+    selection: &S,
+    multi_objective: &M,
+    genomes: Vec<G>,
+    fitness: Vec<F>,
+    n: usize,
+    nan_policy: NanPolicy,
+) -> PopulationResult<G, F>
+where
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = F>,
+    M::Distance: ToF64,
+{
+    debug_assert_eq!(genomes.len(), fitness.len());
+
+    let ranking: Vec<_> = selection
+        .try_select_and_rank(&fitness[..], n, multi_objective, nan_policy)?
+        .iter()
+        .map(|i| (i.index, i.rank, i.crowding_distance))
+        .collect();
+
+    // Move the selected genomes and fitness values out by index.
+    let mut genomes: Vec<_> = genomes.into_iter().map(Some).collect();
+    let mut fitness: Vec<_> = fitness.into_iter().map(Some).collect();
+
+    Ok(ranking
+        .into_iter()
+        .map(|(index, rank, crowding_distance)| Individual {
+            genome: genomes[index].take().unwrap(),
+            fitness: fitness[index].take().unwrap(),
+            rank,
+            crowding_distance,
+        })
+        .collect())
+}
+
+/// Generates `mu` random genomes, rates them and assigns rank and
+/// crowding distance.
+///
+/// Fails if `mu` is 0, or with `NanPolicy::Reject` if a fitness value is
+/// invalid.
+pub fn initial_population<R, D, S, M>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    selection: &S,
+    multi_objective: &M,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+{
+    let genomes: Vec<_> = (0..evo_config.mu)
+        .map(|_| driver.random_genome(rng))
+        .collect();
+    let fitness = driver.rate(&genomes);
+
+    select_and_rank_population(selection, multi_objective, genomes, fitness, evo_config.mu, evo_config.nan_policy)
+}
+
+/// Creates an offspring genome from two parents chosen by tournament
+/// selection (using the crowded-comparison operator).
+pub(crate) fn breed<R, D>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    population: &[Individual<D::Genome, D::Fitness>],
+) -> D::Genome
+where
+    R: Rng,
+    D: Driver,
+{
+    // select two parents.
+    let parent1 = tournament_selection_fast(
+        rng,
+        population,
+        |a, b| a.crowded_cmp(b) == Ordering::Less,
+        evo_config.k,
+    );
+    let parent2 = tournament_selection_fast(
+        rng,
+        population,
+        |a, b| a.crowded_cmp(b) == Ordering::Less,
+        evo_config.k,
+    );
+
+    let mut child = driver.mate(rng, &parent1.genome, &parent2.genome);
+    driver.mutate(rng, &mut child);
+    child
+}
+
+/// Performs one generation of NSGA-II.
+///
+/// Creates `lambda` offspring from parents chosen by tournament
+/// selection (using the crowded-comparison operator), rates them, and
+/// reduces the `mu + lambda` individuals back to `mu` individuals using
+/// `selection`.
+///
+/// Fails with `NanPolicy::Reject` if a fitness value is invalid. The
+/// `solution` of the error is then the index of the individual, the
+/// offspring coming first.
+pub fn generational_step<R, D, S, M>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    selection: &S,
+    multi_objective: &M,
+    population: Vec<Individual<D::Genome, D::Fitness>>,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+{
+    // ------------------------------------------------------
+    // generate offspring (reproduce)
+    // ------------------------------------------------------
+
+    let offspring: Vec<D::Genome> = (0..evo_config.lambda)
+        .map(|_| breed(rng, driver, evo_config, &population))
+        .collect();
+
+    // rate offspring (calculate fitness)
+    let offspring_fitness = driver.rate(&offspring);
+
+    // we now have a population with mu + lambda individuals
+    let mut genomes = offspring;
+    let mut fitness = offspring_fitness;
+    for individual in population {
+        genomes.push(individual.genome);
+        fitness.push(individual.fitness);
+    }
+
+    // assign rank and crowding distance, and reduce to `mu` individuals
+    select_and_rank_population(selection, multi_objective, genomes, fitness, evo_config.mu, evo_config.nan_policy)
+}
+
+/// Performs one step of a steady-state `(mu + 1)` algorithm, like
+/// SMS-EMOA with `SelectHypervolume`.
+///
+/// Creates a single offspring, rates it, and discards the worst of the
+/// `mu + 1` individuals using `selection`. This is `generational_step`
+/// with a `lambda` of 1. To run the steady-state algorithm with
+/// `run_until`, set `lambda` to 1 instead, so that each generation is
+/// a single evaluation.
+///
+/// Each step sorts all `mu + 1` individuals into pareto fronts. For
+/// NSGA-II, the `steady_state` module updates the fronts incrementally
+/// instead.
+pub fn steady_state_step<R, D, S, M>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    selection: &S,
+    multi_objective: &M,
+    population: Vec<Individual<D::Genome, D::Fitness>>,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+{
+    let evo_config = EvoConfig {
+        lambda: 1,
+        ..evo_config.clone()
+    };
+    generational_step(rng, driver, &evo_config, selection, multi_objective, population)
+}
+
+/// Runs NSGA-II for `ngen` generations and returns the final ranked
+/// population.
+///
+/// Fails if `mu` is 0, or with `NanPolicy::Reject` as soon as a fitness
+/// value is invalid.
+pub fn run<R, D, S, M>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    selection: &S,
+    multi_objective: &M,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+{
+    run_observed(rng, driver, evo_config, selection, multi_objective, &mut ())
+}
+
+/// Like `run`, but calls `observer` after the initial population is
+/// created and after each generation.
+pub fn run_observed<R, D, S, M, O>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    selection: &S,
+    multi_objective: &M,
+    observer: &mut O,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+    O: Observer<D::Genome, D::Fitness> + ?Sized,
+{
+    let mut termination = MaxGenerations(evo_config.ngen);
+    run_until(
+        rng,
+        driver,
+        evo_config,
+        selection,
+        multi_objective,
+        &mut termination,
+        observer,
+    )
+}
+
+/// Runs NSGA-II until `termination` is met and returns the final ranked
+/// population. The `ngen` of `evo_config` is ignored.
+///
+/// `observer` and then `termination` are called after the initial
+/// population is created and after each generation.
+pub fn run_until<R, D, S, M, T, O>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    selection: &S,
+    multi_objective: &M,
+    termination: &mut T,
+    observer: &mut O,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    S: SelectAndRank,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+    T: Termination<D::Genome, D::Fitness> + ?Sized,
+    O: Observer<D::Genome, D::Fitness> + ?Sized,
+{
+    let start = Instant::now();
+    let mut population = initial_population(rng, driver, evo_config, selection, multi_objective)?;
+
+    let mut progress = Progress::new(start, 0, evo_config.mu);
+    while !progress.is_finished(&population, multi_objective, termination, observer) {
+        population = generational_step(
+            rng,
+            driver,
+            evo_config,
+            selection,
+            multi_objective,
+            population,
+        )?;
+        progress.next_generation(evo_config.lambda);
+    }
+
+    Ok(population)
+}
+
+#[test]
+fn test_run() {
+    use rand::{SeedableRng, XorShiftRng};
+    use select_nsga::SelectNSGA;
+    use test_helper_objective::SquaresDriver;
+    use vector_objective::VectorMultiObjective;
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mo = VectorMultiObjective::minimize(2);
+    let evo_config = EvoConfig {
+        mu: 20,
+        lambda: 20,
+        k: 2,
+        ngen: 50,
+        nan_policy: NanPolicy::Worst,
+    };
+
+    let driver = SquaresDriver::default();
+    let population = run(&mut rng, &driver, &evo_config, &SelectNSGA::default(), &mo).unwrap();
+
+    assert_eq!(20, population.len());
+    for individual in population.iter() {
+        assert_eq!(0, individual.rank);
+        assert!(individual.genome >= 0.0 && individual.genome <= 2.0);
+    }
+}
+
+#[test]
+fn test_run_observed() {
+    use rand::{SeedableRng, XorShiftRng};
+    use multi_objective::MultiObjective;
+    use observer::GenerationStats;
+    use select_nsga::SelectNSGA;
+    use test_helper_objective::{Objective1, Objective2, PairDriver, Tuple};
+
+    struct Recorder(Vec<(usize, usize)>);
+
+    impl Observer<(usize, usize), Tuple> for Recorder {
+        fn observe(&mut self, stats: &GenerationStats<(usize, usize), Tuple>) {
+            assert!(stats.generation_time <= stats.total_time);
+            assert_eq!(2, stats.objective_stats.len());
+            self.0
+                .push((stats.generation, stats.front_sizes.iter().sum()));
+        }
+    }
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let evo_config = EvoConfig {
+        mu: 10,
+        lambda: 10,
+        k: 2,
+        ngen: 3,
+        nan_policy: NanPolicy::Worst,
+    };
+
+    let mut recorder = Recorder(Vec::new());
+    run_observed(&mut rng, &PairDriver, &evo_config, &SelectNSGA::default(), &mo, &mut recorder).unwrap();
+    assert_eq!(vec![(0, 10), (1, 10), (2, 10), (3, 10)], recorder.0);
+}
+
+#[test]
+fn test_run_until() {
+    use rand::{SeedableRng, XorShiftRng};
+    use multi_objective::MultiObjective;
+    use observer::GenerationStats;
+    use select_nsga::SelectNSGA;
+    use termination::{Any, MaxEvaluations, Predicate};
+    use test_helper_objective::{Objective1, Objective2, PairDriver, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let evo_config = EvoConfig {
+        mu: 10,
+        lambda: 5,
+        k: 2,
+        ngen: 0,
+        nan_policy: NanPolicy::Worst,
+    };
+
+    struct LastGeneration(usize);
+
+    impl<G, F> Observer<G, F> for LastGeneration {
+        fn observe(&mut self, stats: &GenerationStats<G, F>) {
+            self.0 = stats.generation;
+        }
+    }
+
+    // 10 + 4 * 5 evaluations
+    let mut last = LastGeneration(0);
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut termination = MaxEvaluations(28);
+    run_until(
+        &mut rng,
+        &PairDriver,
+        &evo_config,
+        &SelectNSGA::default(),
+        &mo,
+        &mut termination,
+        &mut last,
+    )
+    .unwrap();
+    assert_eq!(4, last.0);
+
+    // the first condition met terminates
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut termination = Any(vec![
+        Box::new(MaxEvaluations(1000)),
+        Box::new(Predicate(|stats: &GenerationStats<_, _>| stats.generation == 2)),
+    ]);
+    run_until(
+        &mut rng,
+        &PairDriver,
+        &evo_config,
+        &SelectNSGA::default(),
+        &mo,
+        &mut termination,
+        &mut last,
+    )
+    .unwrap();
+    assert_eq!(2, last.0);
+}
+
+#[test]
+fn test_run_until_times_initial_population() {
+    use rand::{SeedableRng, XorShiftRng};
+    use std::thread;
+    use std::time::Duration;
+    use observer::GenerationStats;
+    use select_nsga::SelectNSGA;
+    use termination::MaxGenerations;
+    use test_helper_objective::SquaresDriver;
+    use vector_objective::VectorMultiObjective;
+
+    // Each evaluation takes at least a millisecond.
+    struct SlowDriver(SquaresDriver);
+
+    impl Driver for SlowDriver {
+        type Genome = f64;
+        type Fitness = (f64, f64);
+
+        fn random_genome<R: Rng>(&self, rng: &mut R) -> f64 {
+            self.0.random_genome(rng)
+        }
+
+        fn fitness(&self, x: &f64) -> (f64, f64) {
+            thread::sleep(Duration::from_millis(1));
+            self.0.fitness(x)
+        }
+
+        fn mate<R: Rng>(&self, rng: &mut R, parent1: &f64, parent2: &f64) -> f64 {
+            self.0.mate(rng, parent1, parent2)
+        }
+    }
+
+    struct Times(Vec<(Duration, Duration)>);
+
+    impl<G, F> Observer<G, F> for Times {
+        fn observe(&mut self, stats: &GenerationStats<G, F>) {
+            self.0.push((stats.generation_time, stats.total_time));
+        }
+    }
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let evo_config = EvoConfig {
+        mu: 20,
+        lambda: 5,
+        k: 2,
+        ngen: 0,
+        nan_policy: NanPolicy::Worst,
+    };
+    let mut times = Times(Vec::new());
+    run_until(
+        &mut rng,
+        &SlowDriver(SquaresDriver::default()),
+        &evo_config,
+        &SelectNSGA::default(),
+        &VectorMultiObjective::minimize(2),
+        &mut MaxGenerations(1),
+        &mut times,
+    )
+    .unwrap();
+
+    // The rating of the initial population is part of generation 0.
+    assert_eq!(2, times.0.len());
+    assert!(times.0[0].0 >= Duration::from_millis(20));
+    assert!(times.0[0].1 >= Duration::from_millis(20));
+    assert!(times.0[1].1 >= Duration::from_millis(25));
+}
+
+#[test]
+fn test_run_with_nan_fitness() {
+    use rand::{SeedableRng, XorShiftRng};
+    use objective::Objective;
+    use multi_objective::MultiObjective;
+    use observer::HypervolumeObserver;
+    use select_nsga::SelectNSGA;
+    use test_helper_objective::{Coordinate, PartialDriver, Point};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+    let evo_config = EvoConfig {
+        mu: 20,
+        lambda: 20,
+        k: 2,
+        ngen: 20,
+        nan_policy: NanPolicy::Worst,
+    };
+
+    let population = run(&mut rng, &PartialDriver(0.0), &evo_config, &SelectNSGA::default(), &mo).unwrap();
+    assert_eq!(20, population.len());
+    for individual in population.iter() {
+        assert_eq!(0, individual.rank);
+        assert!(individual.genome >= 0.0);
+    }
+
+    // Every fitness is NaN, so that the first front is invalid.
+    let mut observer = HypervolumeObserver::new(&mo, vec![100.0, 100.0]);
+    let population = run_observed(
+        &mut rng,
+        &PartialDriver(f64::INFINITY),
+        &evo_config,
+        &SelectNSGA::default(),
+        &mo,
+        &mut observer,
+    )
+    .unwrap();
+    assert_eq!(20, population.len());
+    assert_eq!(vec![0.0; 21], observer.history);
+
+    // Some of the random genomes are negative.
+    let reject = EvoConfig {
+        nan_policy: NanPolicy::Reject,
+        ..evo_config.clone()
+    };
+    let result = run(&mut rng, &PartialDriver(0.0), &reject, &SelectNSGA::default(), &mo);
+    assert!(matches!(result, Err(SelectionError::InvalidValue { .. })));
+
+    let empty = EvoConfig {
+        mu: 0,
+        ..evo_config
+    };
+    let result = run(&mut rng, &PartialDriver(0.0), &empty, &SelectNSGA::default(), &mo);
+    assert!(matches!(result, Err(SelectionError::NoSolutions)));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_rate_independent_of_threads() {
+    use rayon::ThreadPoolBuilder;
+
+    struct HashDriver;
+
+    impl Driver for HashDriver {
+        type Genome = u64;
+        type Fitness = u64;
+
+        fn random_genome<R: Rng>(&self, rng: &mut R) -> u64 {
+            rng.gen()
+        }
+
+        fn fitness(&self, x: &u64) -> u64 {
+            x.wrapping_mul(0x9e37_79b9_7f4a_7c15).rotate_left(17)
+        }
+
+        fn mate<R: Rng>(&self, _rng: &mut R, parent1: &u64, parent2: &u64) -> u64 {
+            parent1 ^ parent2
+        }
+
+        fn rate(&self, genomes: &[u64]) -> Vec<u64> {
+            rate_parallel(self, genomes)
+        }
+    }
+
+    let genomes: Vec<u64> = (0..1000).collect();
+    let expected: Vec<u64> = genomes.iter().map(|g| HashDriver.fitness(g)).collect();
+
+    for &threads in [1, 2, 8].iter() {
+        let pool = ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+        assert_eq!(expected, pool.install(|| HashDriver.rate(&genomes)));
+    }
+}
+
+#[test]
+fn test_run_non_sync_genome() {
+    use rand::{SeedableRng, XorShiftRng};
+    use std::rc::Rc;
+    use select_nsga::SelectNSGA;
+    use vector_objective::VectorMultiObjective;
+
+    // `Rc` is neither `Send` nor `Sync`. This must compile with any set
+    // of features enabled.
+    struct RcDriver;
+
+    impl Driver for RcDriver {
+        type Genome = Rc<f64>;
+        type Fitness = (f64, f64);
+
+        fn random_genome<R: Rng>(&self, rng: &mut R) -> Rc<f64> {
+            Rc::new(rng.gen_range(-10.0, 10.0))
+        }
+
+        fn fitness(&self, x: &Rc<f64>) -> (f64, f64) {
+            (**x * **x, (**x - 2.0) * (**x - 2.0))
+        }
+
+        fn mate<R: Rng>(&self, _rng: &mut R, parent1: &Rc<f64>, parent2: &Rc<f64>) -> Rc<f64> {
+            Rc::new((**parent1 + **parent2) / 2.0)
+        }
+    }
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let evo_config = EvoConfig {
+        mu: 10,
+        lambda: 10,
+        k: 2,
+        ngen: 5,
+        nan_policy: NanPolicy::Worst,
+    };
+    let population = run(&mut rng, &RcDriver, &evo_config, &SelectNSGA::default(), &VectorMultiObjective::minimize(2)).unwrap();
+    assert_eq!(10, population.len());
+}
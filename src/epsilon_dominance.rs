@@ -0,0 +1,175 @@
+use std::cmp::Ordering;
+use objective::{Objective, ToF64};
+use multi_objective::{objective_vector, ObjectiveSet};
+use non_dominated_sort::DominationOrd;
+
+/// The epsilon-box dominance of Laumanns et al., as used by the
+/// epsilon-MOEA of Deb, Mohan and Mishra.
+///
+/// The objective space is divided into boxes of size `epsilons[i]` in
+/// the `i`-th objective, starting at `origin`. A solution dominates
+/// another, if it's box dominates the other's box. Within the same box,
+/// the usual pareto dominance applies, and of two non-dominated
+/// solutions, the one closer to the best corner of the box dominates.
+///
+/// As the number of boxes is finite, an archive which keeps only
+/// non-dominated solutions according to this relation is bounded.
+pub struct EpsilonBoxDominance<M: ObjectiveSet> {
+    pub multi_objective: M,
+    pub epsilons: Vec<f64>,
+    pub origin: M::Solution,
+}
+
+impl<M> EpsilonBoxDominance<M>
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    pub fn new(multi_objective: M, epsilons: Vec<f64>, origin: M::Solution) -> Self {
+        assert_eq!(multi_objective.num_objectives(), epsilons.len());
+        assert!(epsilons.iter().all(|&e| e > 0.0));
+        EpsilonBoxDominance {
+            multi_objective,
+            epsilons,
+            origin,
+        }
+    }
+
+    /// The position of `solution` in units of `epsilons`, relative to
+    /// `origin`. The integral part is the box of `solution`.
+    pub fn box_position(&self, solution: &M::Solution) -> Vec<f64> {
+        objective_vector(&self.multi_objective, solution, &self.origin)
+            .iter()
+            .zip(self.epsilons.iter())
+            .map(|(v, e)| v / e)
+            .collect()
+    }
+}
+
+/// Compares two solutions at the box positions `a` and `b` by epsilon-box
+/// dominance. `pareto` is the pareto dominance of the solutions.
+pub(crate) fn epsilon_box_ord(a: &[f64], b: &[f64], pareto: Ordering) -> Ordering {
+    if same_box(a, b) {
+        if pareto != Ordering::Equal {
+            pareto
+        } else {
+            corner_distance(a)
+                .partial_cmp(&corner_distance(b))
+                .unwrap_or(Ordering::Equal)
+        }
+    } else {
+        let less = a.iter().zip(b.iter()).any(|(x, y)| x.floor() < y.floor());
+        let greater = a.iter().zip(b.iter()).any(|(x, y)| x.floor() > y.floor());
+        match (less, greater) {
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+/// Whether an epsilon archive rejects a candidate at box position
+/// `candidate` because of a member at `member`. `pareto` is the pareto
+/// dominance of the member over the candidate.
+///
+/// The candidate is rejected if it is dominated by the member, or if
+/// both are in the same box without the candidate dominating the member.
+pub(crate) fn is_rejected_by(candidate: &[f64], member: &[f64], pareto: Ordering) -> bool {
+    match epsilon_box_ord(member, candidate, pareto) {
+        Ordering::Less => true,
+        Ordering::Equal => same_box(member, candidate),
+        Ordering::Greater => false,
+    }
+}
+
+fn same_box(a: &[f64], b: &[f64]) -> bool {
+    a.iter().zip(b.iter()).all(|(x, y)| x.floor() == y.floor())
+}
+
+/// The squared distance of `position` to the best corner of it's box.
+fn corner_distance(position: &[f64]) -> f64 {
+    position.iter().map(|p| (p - p.floor()).powi(2)).sum()
+}
+
+impl<M> ObjectiveSet for EpsilonBoxDominance<M>
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    type Distance = M::Distance;
+
+    fn num_objectives(&self) -> usize {
+        self.multi_objective.num_objectives()
+    }
+
+    fn objective(&self, i: usize) -> &dyn Objective<Solution = M::Solution, Distance = M::Distance> {
+        self.multi_objective.objective(i)
+    }
+}
+
+impl<M> DominationOrd for EpsilonBoxDominance<M>
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    type Solution = M::Solution;
+
+    fn domination_ord(&self, a: &Self::Solution, b: &Self::Solution) -> Ordering {
+        epsilon_box_ord(
+            &self.box_position(a),
+            &self.box_position(b),
+            self.multi_objective.domination_ord(a, b),
+        )
+    }
+}
+
+#[test]
+fn test_epsilon_box_dominance() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let dominance = EpsilonBoxDominance::new(MultiObjective::new(&objectives), vec![1.0, 0.5], vec![0.0, 0.0]);
+
+    assert_eq!(vec![1.5, 1.0], dominance.box_position(&vec![1.5, 0.5]));
+
+    // different boxes: (0, 1) dominates (1, 1), although the solutions
+    // are non-dominated.
+    assert_eq!(Ordering::Less, dominance.domination_ord(&vec![0.5, 0.7], &vec![1.5, 0.6]));
+    assert_eq!(Ordering::Equal, dominance.domination_ord(&vec![0.5, 1.2], &vec![1.5, 0.6]));
+
+    // same box: pareto dominance, then the distance to the corner
+    assert_eq!(Ordering::Greater, dominance.domination_ord(&vec![0.6, 0.3], &vec![0.5, 0.2]));
+    assert_eq!(Ordering::Greater, dominance.domination_ord(&vec![0.1, 0.45], &vec![0.5, 0.2]));
+    assert_eq!(Ordering::Equal, dominance.domination_ord(&vec![0.5, 0.2], &vec![0.5, 0.2]));
+}
+
+#[test]
+fn test_epsilon_box_ord() {
+    // Several solutions share the box (2, 2). Of two non-dominated ones,
+    // the one closer to the corner dominates.
+    assert_eq!(Ordering::Less, epsilon_box_ord(&[2.1, 2.5], &[2.6, 2.2], Ordering::Equal));
+    assert_eq!(Ordering::Greater, epsilon_box_ord(&[2.6, 2.2], &[2.1, 2.5], Ordering::Equal));
+    assert!(is_rejected_by(&[2.6, 2.2], &[2.1, 2.5], Ordering::Equal));
+    assert!(!is_rejected_by(&[2.1, 2.5], &[2.6, 2.2], Ordering::Equal));
+
+    // Pareto dominance within a box takes precedence over the distance
+    // to the corner.
+    assert_eq!(Ordering::Greater, epsilon_box_ord(&[2.1, 2.1], &[2.2, 2.3], Ordering::Greater));
+    assert!(!is_rejected_by(&[2.2, 2.3], &[2.1, 2.1], Ordering::Greater));
+
+    // A tie in the distance to the corner: neither dominates, but the
+    // member keeps the box.
+    assert_eq!(Ordering::Equal, epsilon_box_ord(&[2.2, 2.6], &[2.6, 2.2], Ordering::Equal));
+    assert!(is_rejected_by(&[2.6, 2.2], &[2.2, 2.6], Ordering::Equal));
+    assert!(is_rejected_by(&[2.2, 2.6], &[2.6, 2.2], Ordering::Equal));
+
+    // A candidate in a dominating box evicts the member, however close
+    // the member is to it's corner.
+    assert_eq!(Ordering::Less, epsilon_box_ord(&[1.9, 1.9], &[2.0, 2.0], Ordering::Less));
+    assert!(is_rejected_by(&[2.0, 2.0], &[1.9, 1.9], Ordering::Less));
+
+    // Non-dominated boxes: both are kept.
+    assert_eq!(Ordering::Equal, epsilon_box_ord(&[1.5, 3.5], &[3.5, 1.5], Ordering::Equal));
+    assert!(!is_rejected_by(&[1.5, 3.5], &[3.5, 1.5], Ordering::Equal));
+}
@@ -0,0 +1,207 @@
+use multi_objective::{objective_vector, ObjectiveSet};
+use objective::ToF64;
+
+/// The euclidean distance between two solutions in objective space.
+fn euclidean_distance<M>(multi_objective: &M, a: &M::Solution, b: &M::Solution) -> f64
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    objective_vector(multi_objective, a, b)
+        .iter()
+        .map(|d| d * d)
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// The modified distance used by IGD+. Only those objectives count,
+/// in which `a` is worse than the reference point `z`.
+fn dominance_distance<M>(multi_objective: &M, a: &M::Solution, z: &M::Solution) -> f64
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    objective_vector(multi_objective, a, z)
+        .iter()
+        .map(|&d| d.max(0.0))
+        .map(|d| d * d)
+        .sum::<f64>()
+        .sqrt()
+}
+
+/// For each point in `from`, the minimal `distance` to any point in
+/// `to`, averaged.
+fn mean_min_distance<S, F>(from: &[S], to: &[S], distance: F) -> f64
+where
+    F: Fn(&S, &S) -> f64,
+{
+    assert!(!from.is_empty());
+    assert!(!to.is_empty());
+
+    let sum: f64 = from
+        .iter()
+        .map(|a| to.iter().map(|z| distance(a, z)).fold(f64::INFINITY, f64::min))
+        .sum();
+
+    sum / (from.len() as f64)
+}
+
+/// The generational distance (GD) of `solutions` to the
+/// `reference_front`.
+///
+/// This is the average euclidean distance of each solution to it's
+/// nearest point of the reference front. It measures convergence only.
+/// Lower is better.
+///
+/// Panics if `solutions` or `reference_front` is empty.
+pub fn generational_distance<M>(
+    multi_objective: &M,
+    solutions: &[M::Solution],
+    reference_front: &[M::Solution],
+) -> f64
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    mean_min_distance(solutions, reference_front, |a, z| {
+        euclidean_distance(multi_objective, a, z)
+    })
+}
+
+/// The inverted generational distance (IGD) of `solutions` to the
+/// `reference_front`.
+///
+/// This is the average euclidean distance of each point of the
+/// reference front to it's nearest solution. It measures both
+/// convergence and diversity. Lower is better.
+///
+/// Panics if `solutions` or `reference_front` is empty.
+pub fn inverted_generational_distance<M>(
+    multi_objective: &M,
+    solutions: &[M::Solution],
+    reference_front: &[M::Solution],
+) -> f64
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    mean_min_distance(reference_front, solutions, |z, a| {
+        euclidean_distance(multi_objective, a, z)
+    })
+}
+
+/// The IGD+ indicator of Ishibuchi et al (2015).
+///
+/// Like IGD, but in the distance between a solution and a point of the
+/// reference front, only those objectives count, in which the solution
+/// is worse than the reference point. Unlike IGD, IGD+ is weakly pareto
+/// compliant. Lower is better.
+///
+/// Panics if `solutions` or `reference_front` is empty.
+pub fn inverted_generational_distance_plus<M>(
+    multi_objective: &M,
+    solutions: &[M::Solution],
+    reference_front: &[M::Solution],
+) -> f64
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    mean_min_distance(reference_front, solutions, |z, a| {
+        dominance_distance(multi_objective, a, z)
+    })
+}
+
+/// The additive epsilon indicator of `solutions` with respect to the
+/// `reference_front`.
+///
+/// This is the smallest value `epsilon`, by which all solutions have to
+/// be translated (in each objective), so that each point of the
+/// reference front is weakly dominated by at least one solution. It is
+/// negative, if the solutions strictly dominate the reference front.
+/// Lower is better.
+///
+/// Panics if `solutions` or `reference_front` is empty.
+pub fn additive_epsilon<M>(
+    multi_objective: &M,
+    solutions: &[M::Solution],
+    reference_front: &[M::Solution],
+) -> f64
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    assert!(!solutions.is_empty());
+    assert!(!reference_front.is_empty());
+
+    reference_front
+        .iter()
+        .map(|z| {
+            solutions
+                .iter()
+                .map(|a| {
+                    objective_vector(multi_objective, a, z)
+                        .into_iter()
+                        .fold(f64::NEG_INFINITY, f64::max)
+                })
+                .fold(f64::INFINITY, f64::min)
+        })
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+#[test]
+fn test_distance_indicators() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+
+    let reference_front = vec![Tuple(0, 4), Tuple(4, 0)];
+    let solutions = vec![Tuple(0, 5), Tuple(4, 3)];
+
+    // (0, 5) is at distance 1 to (0, 4), (4, 3) at distance 3 to (4, 0).
+    assert_eq!(2.0, generational_distance(&mo, &solutions, &reference_front));
+    assert_eq!(2.0, inverted_generational_distance(&mo, &solutions, &reference_front));
+    assert_eq!(2.0, inverted_generational_distance_plus(&mo, &solutions, &reference_front));
+    assert_eq!(3.0, additive_epsilon(&mo, &solutions, &reference_front));
+
+    // (1, 3) is better than (2, 2) in the first objective. IGD+ only
+    // counts the second objective.
+    let reference_front = vec![Tuple(2, 2)];
+    let solutions = vec![Tuple(1, 3)];
+    assert_eq!(2.0f64.sqrt(), inverted_generational_distance(&mo, &solutions, &reference_front));
+    assert_eq!(1.0, inverted_generational_distance_plus(&mo, &solutions, &reference_front));
+    assert_eq!(1.0, additive_epsilon(&mo, &solutions, &reference_front));
+
+    // The solutions dominate the reference front.
+    let reference_front = vec![Tuple(2, 2)];
+    let solutions = vec![Tuple(1, 1)];
+    assert_eq!(0.0, inverted_generational_distance_plus(&mo, &solutions, &reference_front));
+    assert_eq!(-1.0, additive_epsilon(&mo, &solutions, &reference_front));
+}
+
+#[test]
+fn test_distance_indicators_mixed_directions() {
+    use multi_objective::MultiObjective;
+    use objective::{Directed, Objective};
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    // minimize the first, maximize the second value
+    let max2 = Directed::maximize(Objective2);
+    let objectives: [&dyn Objective<Solution = Tuple, Distance = f64>; 2] = [&Objective1, &max2];
+    let mo = MultiObjective::new(&objectives);
+
+    // (0, 1) and (4, 5) are each 1 worse in the second objective.
+    let reference_front = vec![Tuple(0, 2), Tuple(4, 6)];
+    let solutions = vec![Tuple(0, 1), Tuple(4, 5)];
+    assert_eq!(1.0, generational_distance(&mo, &solutions, &reference_front));
+    assert_eq!(1.0, inverted_generational_distance_plus(&mo, &solutions, &reference_front));
+    assert_eq!(1.0, additive_epsilon(&mo, &solutions, &reference_front));
+
+    // (1, 7) is better than (0, 6) in the second objective, but worse
+    // in the first.
+    let reference_front = vec![Tuple(0, 6)];
+    let solutions = vec![Tuple(1, 7)];
+    assert_eq!(1.0, inverted_generational_distance_plus(&mo, &solutions, &reference_front));
+    assert_eq!(1.0, additive_epsilon(&mo, &solutions, &reference_front));
+}
@@ -0,0 +1,227 @@
+use multi_objective::{objective_vector, ObjectiveSet};
+use objective::ToF64;
+use nan_worst::is_valid;
+use std::cmp::Ordering;
+
+/// Calculates the hypervolume of `solutions` with respect to the
+/// `reference` point.
+///
+/// The hypervolume is the volume of the region in objective space,
+/// which is dominated by at least one of the solutions and which itself
+/// dominates the reference point. Larger is better.
+///
+/// Solutions that do not strictly dominate the reference point in each
+/// objective, or that have invalid objective values (see
+/// `nan_worst::is_valid`), do not contribute to the hypervolume.
+///
+/// For two and three objectives the hypervolume is calculated by a
+/// sweep over the sorted solutions. For more objectives the WFG
+/// algorithm is used.
+pub fn hypervolume<M>(multi_objective: &M, solutions: &[M::Solution], reference: &M::Solution) -> f64
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    let points = relative_points(multi_objective, solutions, reference);
+    hypervolume_of_points(points, multi_objective.num_objectives())
+}
+
+/// Transforms `solutions` into points, which all lie in the positive
+/// orthant, with `reference` being the origin. Larger coordinates are
+/// better. Solutions that do not strictly dominate `reference`, and
+/// invalid solutions, are omitted.
+pub(crate) fn relative_points<M>(
+    multi_objective: &M,
+    solutions: &[M::Solution],
+    reference: &M::Solution,
+) -> Vec<Vec<f64>>
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    solutions
+        .iter()
+        .filter(|solution| is_valid(multi_objective, solution))
+        .map(|solution| {
+            objective_vector(multi_objective, solution, reference)
+                .into_iter()
+                .map(|x| -x)
+                .collect::<Vec<_>>()
+        })
+        .filter(|point| point.iter().all(|&x| x > 0.0))
+        .collect()
+}
+
+/// The volume of the union of the boxes spanned by the origin and each
+/// of the `points`. All coordinates must be positive.
+pub(crate) fn hypervolume_of_points(points: Vec<Vec<f64>>, dimensions: usize) -> f64 {
+    if points.is_empty() {
+        return 0.0;
+    }
+
+    debug_assert!(points.iter().all(|p| p.len() == dimensions));
+
+    match dimensions {
+        0 => 0.0,
+        1 => points.iter().map(|p| p[0]).fold(0.0, f64::max),
+        2 => hypervolume_2d(points),
+        3 => hypervolume_3d(points),
+        _ => hypervolume_wfg(points),
+    }
+}
+
+fn descending_by(i: usize) -> impl Fn(&Vec<f64>, &Vec<f64>) -> Ordering {
+    move |a, b| b[i].partial_cmp(&a[i]).unwrap()
+}
+
+fn hypervolume_2d(mut points: Vec<Vec<f64>>) -> f64 {
+    points.sort_by(descending_by(0));
+
+    // Sweep along the first objective, adding the area that each point
+    // adds above the highest point so far.
+    let mut volume = 0.0;
+    let mut height = 0.0;
+    for p in points.iter() {
+        if p[1] > height {
+            volume += p[0] * (p[1] - height);
+            height = p[1];
+        }
+    }
+    volume
+}
+
+fn hypervolume_3d(mut points: Vec<Vec<f64>>) -> f64 {
+    points.sort_by(descending_by(2));
+
+    // Sweep along the third objective. Between two consecutive levels,
+    // the cross section is the 2d hypervolume of all points above.
+    let mut volume = 0.0;
+    for i in 0..points.len() {
+        let next_level = points.get(i + 1).map_or(0.0, |p| p[2]);
+        let depth = points[i][2] - next_level;
+        if depth > 0.0 {
+            let slice: Vec<_> = points[..i + 1].iter().map(|p| vec![p[0], p[1]]).collect();
+            volume += hypervolume_2d(slice) * depth;
+        }
+    }
+    volume
+}
+
+/// The WFG algorithm of While, Bradstreet and Barone (2012). The
+/// hypervolume is the sum of the exclusive hypervolumes of the points,
+/// each calculated relative to the points that follow it.
+fn hypervolume_wfg(mut points: Vec<Vec<f64>>) -> f64 {
+    let dimensions = points[0].len();
+    points.sort_by(descending_by(dimensions - 1));
+
+    (0..points.len())
+        .map(|i| exclusive_hypervolume(&points[i], &points[i + 1..]))
+        .sum()
+}
+
+/// The hypervolume that is dominated by `point` but by none of `others`.
+pub(crate) fn exclusive_hypervolume(point: &[f64], others: &[Vec<f64>]) -> f64 {
+    let inclusive: f64 = point.iter().product();
+    inclusive - hypervolume_of_points(limit_set(point, others), point.len())
+}
+
+/// Limits each point in `others` to the box spanned by `point` and
+/// removes the dominated points of the result.
+fn limit_set(point: &[f64], others: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let limited: Vec<Vec<f64>> = others
+        .iter()
+        .map(|q| q.iter().zip(point.iter()).map(|(&a, &b)| a.min(b)).collect())
+        .collect();
+
+    let mut result: Vec<Vec<f64>> = Vec::with_capacity(limited.len());
+    for (i, p) in limited.iter().enumerate() {
+        // `p` is dominated, if another point is at least as good in all
+        // objectives. Of duplicates, keep the first one.
+        let dominated = limited.iter().enumerate().any(|(j, q)| {
+            j != i && q.iter().zip(p.iter()).all(|(a, b)| a >= b) && (q != p || j < i)
+        });
+        if !dominated {
+            result.push(p.clone());
+        }
+    }
+    result
+}
+
+#[test]
+fn test_hypervolume_2d() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+
+    // A staircase of three steps of width and height 1. The dominated
+    // point (3, 3) and the point (5, 0), which does not dominate the
+    // reference point, do not contribute.
+    let solutions = vec![Tuple(1, 3), Tuple(2, 2), Tuple(3, 1), Tuple(3, 3), Tuple(5, 0)];
+    assert_eq!(6.0, hypervolume(&mo, &solutions, &Tuple(4, 4)));
+
+    assert_eq!(0.0, hypervolume(&mo, &[], &Tuple(4, 4)));
+}
+
+#[test]
+fn test_hypervolume_mixed_directions() {
+    use multi_objective::MultiObjective;
+    use objective::{Directed, Objective};
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    // minimize the first, maximize the second value
+    let max2 = Directed::maximize(Objective2);
+    let objectives: [&dyn Objective<Solution = Tuple, Distance = f64>; 2] = [&Objective1, &max2];
+    let mo = MultiObjective::new(&objectives);
+
+    // Boxes of 3 x 3 and 2 x 4, overlapping by 2 x 3. (5, 5) does not
+    // dominate the reference point.
+    let solutions = vec![Tuple(1, 3), Tuple(2, 4), Tuple(5, 5)];
+    assert_eq!(11.0, hypervolume(&mo, &solutions, &Tuple(4, 0)));
+}
+
+#[test]
+fn test_hypervolume_3d() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let mo = MultiObjective::<Point, f64>::new(&[&Coordinate(0), &Coordinate(1), &Coordinate(2)]);
+    let reference = vec![2.0, 3.0, 4.0];
+
+    assert_eq!(6.0, hypervolume(&mo, &[vec![1.0, 1.0, 1.0]], &reference));
+
+    // Three boxes of volume 2, pairwise overlapping by volume 1, all
+    // three overlapping by volume 1: 3 * 2 - 3 * 1 + 1 = 4.
+    let solutions = vec![vec![0.0, 2.0, 3.0], vec![1.0, 1.0, 3.0], vec![1.0, 2.0, 2.0]];
+    assert_eq!(4.0, hypervolume(&mo, &solutions, &reference));
+}
+
+#[test]
+fn test_hypervolume_wfg() {
+    use rand::{Rng, SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+
+    // WFG must agree with the 3d sweep.
+    for _ in 0..20 {
+        let points: Vec<Vec<f64>> = (0..15)
+            .map(|_| (0..3).map(|_| rng.gen_range(0.1, 1.0)).collect())
+            .collect();
+
+        let expected = hypervolume_3d(points.clone());
+        let actual = hypervolume_wfg(points.clone());
+        assert!((expected - actual).abs() < 1e-9);
+
+        // Extending each point by a constant fourth coordinate
+        // multiplies the hypervolume by that constant.
+        let points_4d: Vec<Vec<f64>> = points
+            .into_iter()
+            .map(|mut p| {
+                p.push(2.0);
+                p
+            })
+            .collect();
+        let actual = hypervolume_of_points(points_4d, 4);
+        assert!((2.0 * expected - actual).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,10 @@
+//! Performance indicators measure the quality of a set of solutions,
+//! usually of the pareto front returned by a selection.
+//!
+//! All indicators work in objective space. The objective values of a
+//! solution are obtained from the objectives themselves, see
+//! `multi_objective::objective_vector`. Hence, maximized objectives
+//! (see `objective::Directed`) are handled like minimized ones.
+
+pub mod hypervolume;
+pub mod distance;
@@ -1,12 +1,43 @@
 extern crate non_dominated_sort;
 extern crate rand;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde;
+#[cfg(test)]
+#[macro_use]
+extern crate proptest;
+#[cfg(all(test, feature = "serde"))]
+extern crate bincode;
 
 pub mod objective;
 pub mod multi_objective;
+pub mod vector_objective;
+pub mod constraint;
+pub mod constrained_multi_objective;
+pub mod nan_worst;
+pub mod epsilon_dominance;
 pub mod crowding_distance;
+pub mod sorting;
 pub mod selection;
 pub mod tournament_selection;
 pub mod select_nsga;
+pub mod select_nsga3;
+pub mod select_epsilon;
+pub mod select_spea2;
+pub mod select_hypervolume;
+pub mod engine;
+pub mod steady_state;
+pub mod async_engine;
+pub mod observer;
+pub mod termination;
+pub mod moead;
+pub mod checkpoint;
+pub mod archive;
+pub mod indicator;
+pub mod operators;
+pub mod problems;
 
 #[cfg(test)]
 mod test_helper_objective;
@@ -0,0 +1,501 @@
+//! MOEA/D, the multi-objective evolutionary algorithm based on
+//! decomposition of Zhang and Li (2007).
+//!
+//! The problem is decomposed into scalar subproblems, one for each of a
+//! set of evenly spread weight vectors. Each subproblem keeps the best
+//! solution found for it. Offspring are created from the solutions of
+//! neighboring subproblems (those with close weight vectors), and
+//! replace the solutions of neighboring subproblems they improve.
+//!
+//! The problem is defined by an `engine::Driver` and an objective set,
+//! just as for NSGA-II.
+
+use rand::Rng;
+use std::cmp::Ordering;
+use std::time::Instant;
+use engine::{Driver, Individual, PopulationResult};
+use multi_objective::{objective_vector, ObjectiveSet};
+use objective::{nan_worst_cmp, ToF64};
+use nan_worst::is_valid;
+use observer::Observer;
+use select_nsga::SelectNSGA;
+use select_nsga3::das_dennis_reference_points;
+use selection::{check_nan_policy, NanPolicy, SelectAndRank, SelectionError};
+use termination::{MaxGenerations, Progress, Termination};
+
+/// A scalarizing function, which decomposes the objective values into
+/// the single value of a subproblem. Less is better.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Scalarizing {
+    /// The weighted sum of the objective values. Cannot reach the
+    /// solutions in non-convex parts of the pareto front.
+    WeightedSum,
+
+    /// The largest weighted distance to the ideal point in any
+    /// objective.
+    #[default]
+    Tchebycheff,
+
+    /// The penalty-based boundary intersection: the distance to the
+    /// ideal point along the weight vector, plus `theta` times the
+    /// perpendicular distance to the weight vector.
+    PenaltyBoundaryIntersection { theta: f64 },
+}
+
+impl Scalarizing {
+    /// The value of `objectives` for the subproblem with `weights`,
+    /// relative to the `ideal` point.
+    ///
+    /// The value is NaN if any of the `objectives` is NaN.
+    pub fn value(&self, objectives: &[f64], weights: &[f64], ideal: &[f64]) -> f64 {
+        if objectives.iter().any(|f| f.is_nan()) {
+            return f64::NAN;
+        }
+        let relative = objectives.iter().zip(ideal.iter()).map(|(f, z)| f - z);
+        match *self {
+            Scalarizing::WeightedSum => relative.zip(weights.iter()).map(|(f, w)| w * f).sum(),
+            Scalarizing::Tchebycheff => relative
+                .zip(weights.iter())
+                .map(|(f, &w)| w.max(1e-6) * f.abs())
+                .fold(f64::NEG_INFINITY, f64::max),
+            Scalarizing::PenaltyBoundaryIntersection { theta } => {
+                let relative: Vec<f64> = relative.collect();
+                let norm = weights.iter().map(|w| w * w).sum::<f64>().sqrt();
+                let d1 = relative.iter().zip(weights.iter()).map(|(f, w)| f * w).sum::<f64>() / norm;
+                let d2 = relative
+                    .iter()
+                    .zip(weights.iter())
+                    .map(|(f, w)| (f - d1 * w / norm) * (f - d1 * w / norm))
+                    .sum::<f64>()
+                    .sqrt();
+                d1 + theta * d2
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MoeadConfig {
+    /// The number of divisions per objective of the Das-Dennis weight
+    /// vectors (see `select_nsga3::das_dennis_reference_points`). The
+    /// population size is the number of weight vectors.
+    pub divisions: usize,
+    /// number of neighbors of a subproblem, including itself
+    pub neighborhood_size: usize,
+    /// probability to choose the parents from the neighborhood instead
+    /// of the whole population
+    pub neighborhood_probability: f64,
+    /// max number of solutions replaced by an offspring
+    pub max_replacements: usize,
+    /// scalarizing function of the subproblems
+    pub scalarizing: Scalarizing,
+    /// max number of generations
+    pub ngen: usize,
+    /// how to treat individuals with invalid (e.g. NaN) fitness values
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub nan_policy: NanPolicy,
+}
+
+/// The state of a MOEA/D run.
+struct Decomposition<G, F> {
+    weights: Vec<Vec<f64>>,
+    neighborhoods: Vec<Vec<usize>>,
+    /// The objective values are relative to the fitness of an arbitrary
+    /// valid solution (see `nan_worst::is_valid`), which is the same for
+    /// all solutions. Until there is a valid solution, all objective
+    /// values are NaN.
+    origin: Option<F>,
+    ideal: Vec<f64>,
+    /// The solution of each subproblem and it's objective values.
+    solutions: Vec<(G, F, Vec<f64>)>,
+}
+
+/// The objective values of `fitness` relative to `origin`, or NaN for
+/// an invalid `fitness` (see `nan_worst::is_valid`) or without an
+/// origin. The objectives are never called with an invalid `fitness`
+/// other than by `is_valid`.
+fn objectives<M>(multi_objective: &M, fitness: &M::Solution, origin: Option<&M::Solution>) -> Vec<f64>
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    match origin {
+        Some(origin) if is_valid(multi_objective, fitness) => objective_vector(multi_objective, fitness, origin),
+        _ => vec![f64::NAN; multi_objective.num_objectives()],
+    }
+}
+
+/// The indices of the `size` nearest weight vectors of each weight
+/// vector, nearest first.
+fn neighborhoods(weights: &[Vec<f64>], size: usize) -> Vec<Vec<usize>> {
+    weights
+        .iter()
+        .map(|w| {
+            let distance = |j: usize| -> f64 {
+                w.iter()
+                    .zip(weights[j].iter())
+                    .map(|(a, b)| (a - b) * (a - b))
+                    .sum()
+            };
+            let mut neighbors: Vec<usize> = (0..weights.len()).collect();
+            neighbors.sort_by(|&a, &b| distance(a).partial_cmp(&distance(b)).unwrap().then(a.cmp(&b)));
+            neighbors.truncate(size.max(1));
+            neighbors
+        })
+        .collect()
+}
+
+impl<G, F> Decomposition<G, F>
+where
+    G: Clone,
+    F: Clone,
+{
+    fn new<R, D, M>(rng: &mut R, driver: &D, config: &MoeadConfig, multi_objective: &M) -> Result<Self, SelectionError>
+    where
+        R: Rng,
+        D: Driver<Genome = G, Fitness = F>,
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let weights = das_dennis_reference_points(multi_objective.num_objectives(), config.divisions.max(1));
+        let genomes: Vec<G> = (0..weights.len()).map(|_| driver.random_genome(rng)).collect();
+        let fitness = driver.rate(&genomes);
+        if fitness.is_empty() {
+            return Err(SelectionError::NoSolutions);
+        }
+        for (index, fitness) in fitness.iter().enumerate() {
+            check_nan_policy(index, fitness, multi_objective, config.nan_policy)?;
+        }
+        let origin = fitness.iter().find(|f| is_valid(multi_objective, f)).cloned();
+
+        let solutions: Vec<_> = genomes
+            .into_iter()
+            .zip(fitness)
+            .map(|(genome, fitness)| {
+                let objectives = objectives(multi_objective, &fitness, origin.as_ref());
+                (genome, fitness, objectives)
+            })
+            .collect();
+
+        let ideal = (0..multi_objective.num_objectives())
+            .map(|i| solutions.iter().map(|s| s.2[i]).fold(f64::INFINITY, f64::min))
+            .collect();
+
+        Ok(Decomposition {
+            neighborhoods: neighborhoods(&weights, config.neighborhood_size),
+            weights,
+            origin,
+            ideal,
+            solutions,
+        })
+    }
+
+    /// Creates, rates and inserts one offspring per subproblem. With
+    /// `NanPolicy::Reject`, fails at the first invalid offspring, which
+    /// counts as the solution after the solutions of all subproblems and
+    /// the offspring before it.
+    fn step<R, D, M>(
+        &mut self,
+        rng: &mut R,
+        driver: &D,
+        config: &MoeadConfig,
+        multi_objective: &M,
+    ) -> Result<(), SelectionError>
+    where
+        R: Rng,
+        D: Driver<Genome = G, Fitness = F>,
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let all: Vec<usize> = (0..self.solutions.len()).collect();
+
+        for i in 0..self.solutions.len() {
+            let mut pool = if rng.gen::<f64>() < config.neighborhood_probability {
+                self.neighborhoods[i].clone()
+            } else {
+                all.clone()
+            };
+
+            let parent1 = pool[rng.gen_range(0, pool.len())];
+            let parent2 = pool[rng.gen_range(0, pool.len())];
+            let mut child = driver.mate(rng, &self.solutions[parent1].0, &self.solutions[parent2].0);
+            driver.mutate(rng, &mut child);
+            let fitness = driver.fitness(&child);
+            check_nan_policy(self.solutions.len() + i, &fitness, multi_objective, config.nan_policy)?;
+            if self.origin.is_none() && is_valid(multi_objective, &fitness) {
+                self.origin = Some(fitness.clone());
+            }
+            let objectives = objectives(multi_objective, &fitness, self.origin.as_ref());
+
+            // NaN is ignored
+            for (z, &f) in self.ideal.iter_mut().zip(objectives.iter()) {
+                *z = z.min(f);
+            }
+
+            // replace the solutions of subproblems the child improves.
+            // NaN is worse than any value, and a child replaces a NaN
+            // incumbent unless the child is NaN, too.
+            rng.shuffle(&mut pool);
+            let mut replaced = 0;
+            for &j in pool.iter() {
+                if replaced >= config.max_replacements.max(1) {
+                    break;
+                }
+                let g_child = config.scalarizing.value(&objectives, &self.weights[j], &self.ideal);
+                let g_j = config.scalarizing.value(&self.solutions[j].2, &self.weights[j], &self.ideal);
+                if nan_worst_cmp(g_child, g_j) != Ordering::Greater {
+                    self.solutions[j] = (child.clone(), fitness.clone(), objectives.clone());
+                    replaced += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The solutions of all subproblems, ordered by subproblem, with the
+    /// rank and crowding distance assigned by `SelectNSGA`.
+    fn population<M>(&self, multi_objective: &M) -> PopulationResult<G, F>
+    where
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let fitness: Vec<F> = self.solutions.iter().map(|s| s.1.clone()).collect();
+        let mut population: Vec<_> = self.solutions
+            .iter()
+            .map(|(genome, fitness, _)| Individual {
+                genome: genome.clone(),
+                fitness: fitness.clone(),
+                rank: 0,
+                crowding_distance: 0.0,
+            })
+            .collect();
+
+        let ranking = SelectNSGA::default()
+            .try_select_and_rank(&fitness, fitness.len(), multi_objective, NanPolicy::Worst)?;
+        for ranked in ranking {
+            population[ranked.index].rank = ranked.rank;
+            population[ranked.index].crowding_distance = ranked.crowding_distance;
+        }
+        Ok(population)
+    }
+}
+
+/// Runs MOEA/D for `ngen` generations and returns the solution of each
+/// subproblem.
+///
+/// The rank and crowding distance of the returned individuals are
+/// assigned by `SelectNSGA` and do not influence the search. Solutions
+/// of different subproblems can be dominated, or duplicates.
+///
+/// Fails like `engine::run`: if there are no subproblems, or with
+/// `NanPolicy::Reject` as soon as a fitness value is invalid. With
+/// `NanPolicy::Worst`, the scalarized value of an invalid solution is
+/// worse than that of any valid solution.
+pub fn run_moead<R, D, M>(
+    rng: &mut R,
+    driver: &D,
+    config: &MoeadConfig,
+    multi_objective: &M,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    D::Genome: Clone,
+    D::Fitness: Clone,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+{
+    let mut termination = MaxGenerations(config.ngen);
+    run_moead_until(rng, driver, config, multi_objective, &mut termination, &mut ())
+}
+
+/// Like `run_moead`, but runs until `termination` is met. The `ngen` of
+/// `config` is ignored.
+///
+/// `observer` and then `termination` are called after the initial
+/// population is created and after each generation, in which every
+/// subproblem creates one offspring.
+pub fn run_moead_until<R, D, M, T, O>(
+    rng: &mut R,
+    driver: &D,
+    config: &MoeadConfig,
+    multi_objective: &M,
+    termination: &mut T,
+    observer: &mut O,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    D::Genome: Clone,
+    D::Fitness: Clone,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+    T: Termination<D::Genome, D::Fitness> + ?Sized,
+    O: Observer<D::Genome, D::Fitness> + ?Sized,
+{
+    let start = Instant::now();
+    let mut decomposition = Decomposition::new(rng, driver, config, multi_objective)?;
+
+    let mut progress = Progress::new(start, 0, decomposition.solutions.len());
+    loop {
+        let population = decomposition.population(multi_objective)?;
+        if progress.is_finished(&population, multi_objective, termination, observer) {
+            return Ok(population);
+        }
+
+        decomposition.step(rng, driver, config, multi_objective)?;
+        progress.next_generation(decomposition.solutions.len());
+    }
+}
+
+#[test]
+fn test_scalarizing() {
+    let ideal = [1.0, 1.0];
+    let weights = [0.25, 0.75];
+
+    assert_eq!(2.5, Scalarizing::WeightedSum.value(&[2.0, 4.0], &weights, &ideal));
+    assert_eq!(2.25, Scalarizing::Tchebycheff.value(&[2.0, 4.0], &weights, &ideal));
+
+    // On the weight vector, there is no penalty.
+    let pbi = Scalarizing::PenaltyBoundaryIntersection { theta: 5.0 };
+    assert!((pbi.value(&[2.0, 4.0], &weights, &ideal) - 10.0_f64.sqrt()).abs() < 1e-12);
+    // Perpendicular to the weight vector (1, 1) / sqrt(2).
+    let d = pbi.value(&[2.0, 0.0], &[0.5, 0.5], &ideal);
+    assert!((d - 5.0 * 2.0_f64.sqrt()).abs() < 1e-12);
+
+    // A zero weight is replaced by a small one.
+    assert!(Scalarizing::Tchebycheff.value(&[1.0, 5.0], &[1.0, 0.0], &ideal) > 0.0);
+}
+
+#[test]
+fn test_neighborhoods() {
+    let weights = das_dennis_reference_points(2, 4);
+    let neighborhoods = neighborhoods(&weights, 3);
+    assert_eq!(vec![0, 1, 2], neighborhoods[0]);
+    assert_eq!(vec![2, 1, 3], neighborhoods[2]);
+    assert_eq!(vec![4, 3, 2], neighborhoods[4]);
+}
+
+#[test]
+fn test_run_moead() {
+    use rand::{SeedableRng, XorShiftRng};
+    use test_helper_objective::SquaresDriver;
+    use vector_objective::VectorMultiObjective;
+
+    let driver = SquaresDriver { noise: 0.1 };
+    let mo = VectorMultiObjective::minimize(2);
+
+    for &scalarizing in [
+        Scalarizing::WeightedSum,
+        Scalarizing::Tchebycheff,
+        Scalarizing::PenaltyBoundaryIntersection { theta: 5.0 },
+    ].iter()
+    {
+        let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+        let config = MoeadConfig {
+            divisions: 10,
+            neighborhood_size: 3,
+            neighborhood_probability: 0.9,
+            max_replacements: 2,
+            scalarizing,
+            ngen: 50,
+            nan_policy: NanPolicy::Worst,
+        };
+
+        let population = run_moead(&mut rng, &driver, &config, &mo).unwrap();
+        assert_eq!(11, population.len());
+        for individual in population.iter() {
+            assert_eq!(0, individual.rank);
+            assert!(individual.genome > -0.1 && individual.genome < 2.1);
+        }
+
+        // Both ends of the pareto front are approached.
+        let genomes = population.iter().map(|i| i.genome);
+        assert!(genomes.clone().fold(f64::INFINITY, f64::min) < 0.5);
+        assert!(genomes.fold(f64::NEG_INFINITY, f64::max) > 1.5);
+    }
+}
+
+#[test]
+fn test_run_moead_with_nan_fitness() {
+    use std::cell::Cell;
+    use rand::{SeedableRng, XorShiftRng};
+    use multi_objective::MultiObjective;
+    use objective::Objective;
+    use test_helper_objective::{Coordinate, Point, SquaresDriver};
+    use vector_objective::VectorMultiObjective;
+
+    // The first objective is NaN for negative genomes, and the first
+    // random genome is negative.
+    struct NanDriver {
+        driver: SquaresDriver,
+        first: Cell<bool>,
+    }
+
+    impl Driver for NanDriver {
+        type Genome = f64;
+        type Fitness = Point;
+
+        fn random_genome<R: Rng>(&self, rng: &mut R) -> f64 {
+            if self.first.replace(false) {
+                -1.0
+            } else {
+                self.driver.random_genome(rng)
+            }
+        }
+
+        fn fitness(&self, x: &f64) -> Point {
+            let (f1, f2) = self.driver.fitness(x);
+            vec![if *x < 0.0 { f64::NAN } else { f1 }, f2]
+        }
+
+        fn mate<R: Rng>(&self, rng: &mut R, parent1: &f64, parent2: &f64) -> f64 {
+            self.driver.mate(rng, parent1, parent2)
+        }
+    }
+
+    let config = MoeadConfig {
+        divisions: 10,
+        neighborhood_size: 3,
+        neighborhood_probability: 0.9,
+        max_replacements: 2,
+        scalarizing: Scalarizing::Tchebycheff,
+        ngen: 50,
+        nan_policy: NanPolicy::Worst,
+    };
+
+    // All solutions with NaN fitness are replaced. `Coordinate` panics
+    // if it compares NaN.
+    let check = |population: Vec<Individual<f64, Point>>| {
+        assert_eq!(11, population.len());
+        for individual in population.iter() {
+            assert!(!individual.fitness[0].is_nan());
+            assert_eq!(0, individual.rank);
+            assert!(individual.genome >= 0.0 && individual.genome < 2.1);
+        }
+    };
+    let driver = || NanDriver {
+        driver: SquaresDriver { noise: 0.1 },
+        first: Cell::new(true),
+    };
+
+    let mo = VectorMultiObjective::minimize(2);
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    check(run_moead(&mut rng, &driver(), &config, &mo).unwrap());
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    check(run_moead(&mut rng, &driver(), &config, &mo).unwrap());
+
+    // The first random genome is invalid.
+    let reject = MoeadConfig {
+        nan_policy: NanPolicy::Reject,
+        ..config
+    };
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let result = run_moead(&mut rng, &driver(), &reject, &mo);
+    assert_eq!(Err(SelectionError::InvalidValue { solution: 0, objective: 0 }), result.map(|p| p.len()));
+}
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
 use std::marker::PhantomData;
-use objective::Objective;
+use objective::{Objective, ToF64};
 use non_dominated_sort::DominationOrd;
 
 pub struct MultiObjective<'a, S, D>
@@ -8,7 +8,7 @@ where
     S: 'a,
     D: 'a,
 {
-    pub objectives: &'a [&'a Objective<Solution = S, Distance = D>],
+    pub objectives: &'a [&'a dyn Objective<Solution = S, Distance = D>],
     _solution: PhantomData<S>,
     _distance: PhantomData<D>,
 }
@@ -18,7 +18,7 @@ where
     S: 'a,
     D: 'a,
 {
-    pub fn new(objectives: &'a [&'a Objective<Solution = S, Distance = D>]) -> Self {
+    pub fn new(objectives: &'a [&'a dyn Objective<Solution = S, Distance = D>]) -> Self {
         Self {
             objectives,
             _solution: PhantomData,
@@ -27,6 +27,109 @@ where
     }
 }
 
+/// A dominance relation which is defined on a list of objectives.
+///
+/// Besides the dominance relation itself, selection strategies like
+/// NSGA need access to the individual objectives, for instance to
+/// calculate the crowding distance of a solution.
+pub trait ObjectiveSet: DominationOrd {
+    /// The output type of the distance metric of the objectives.
+    type Distance;
+
+    /// The number of objectives.
+    fn num_objectives(&self) -> usize;
+
+    /// Returns the `i`-th objective.
+    fn objective(&self, i: usize)
+        -> &dyn Objective<Solution = Self::Solution, Distance = Self::Distance>;
+
+    /// Whether `domination_ord` is the plain pareto dominance of the
+    /// objectives. Sorting algorithms which work on the objective values
+    /// directly rely on this, and otherwise fall back to using
+    /// `domination_ord`.
+    ///
+    /// Defaults to `false`, which is always correct. Implementations
+    /// whose `domination_ord` is `pareto_dominance` opt in.
+    fn is_pareto_dominance(&self) -> bool {
+        false
+    }
+}
+
+/// Returns the position of `solution` in objective space, relative to
+/// `origin`.
+///
+/// The `i`-th component is the absolute distance between `solution` and
+/// `origin` according to the `i`-th objective. It is negative, if
+/// `solution` is better than `origin` according to that objective.
+/// Hence, as for the total order of an objective, less is better.
+pub fn objective_vector<M>(multi_objective: &M, solution: &M::Solution, origin: &M::Solution) -> Vec<f64>
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    (0..multi_objective.num_objectives())
+        .map(|i| {
+            let objective = multi_objective.objective(i);
+            let distance = objective.distance(solution, origin).to_f64();
+            let distance = distance.abs();
+            match objective.total_order(solution, origin) {
+                Ordering::Less => -distance,
+                Ordering::Equal => 0.0,
+                Ordering::Greater => distance,
+            }
+        })
+        .collect()
+}
+
+/// The pareto dominance of the objectives of `multi_objective`: `a`
+/// dominates `b` if it is better in at least one objective and not
+/// worse in any.
+pub fn pareto_dominance<M: ObjectiveSet>(multi_objective: &M, a: &M::Solution, b: &M::Solution) -> Ordering {
+    let mut less_cnt = 0;
+    let mut greater_cnt = 0;
+
+    for i in 0..multi_objective.num_objectives() {
+        match multi_objective.objective(i).total_order(a, b) {
+            Ordering::Less => {
+                less_cnt += 1;
+            }
+            Ordering::Greater => {
+                greater_cnt += 1;
+            }
+            Ordering::Equal => {}
+        }
+    }
+
+    if less_cnt > 0 && greater_cnt == 0 {
+        Ordering::Less
+    } else if greater_cnt > 0 && less_cnt == 0 {
+        Ordering::Greater
+    } else {
+        debug_assert!((less_cnt > 0 && greater_cnt > 0) || (less_cnt == 0 && greater_cnt == 0));
+        Ordering::Equal
+    }
+}
+
+impl<'a, S, D> ObjectiveSet for MultiObjective<'a, S, D>
+where
+    S: 'a,
+    D: 'a,
+{
+    type Distance = D;
+
+    fn num_objectives(&self) -> usize {
+        self.objectives.len()
+    }
+
+    fn objective(&self, i: usize) -> &dyn Objective<Solution = S, Distance = D> {
+        self.objectives[i]
+    }
+
+    fn is_pareto_dominance(&self) -> bool {
+        true
+    }
+}
+
 impl<'a, S, D> DominationOrd for MultiObjective<'a, S, D>
 where
     S: 'a,
@@ -35,30 +138,72 @@ where
     type Solution = S;
 
     fn domination_ord(&self, a: &Self::Solution, b: &Self::Solution) -> Ordering {
-        let mut less_cnt = 0;
-        let mut greater_cnt = 0;
-
-        for objective in self.objectives.iter() {
-            match objective.total_order(a, b) {
-                Ordering::Less => {
-                    less_cnt += 1;
-                }
-                Ordering::Greater => {
-                    greater_cnt += 1;
-                }
-                Ordering::Equal => {}
-            }
-        }
+        pareto_dominance(self, a, b)
+    }
+}
 
-        if less_cnt > 0 && greater_cnt == 0 {
-            Ordering::Less
-        } else if greater_cnt > 0 && less_cnt == 0 {
-            Ordering::Greater
-        } else {
-            debug_assert!((less_cnt > 0 && greater_cnt > 0) || (less_cnt == 0 && greater_cnt == 0));
-            Ordering::Equal
-        }
+/// A boxed objective, which can be shared across threads.
+pub type BoxedObjective<S, D> = Box<dyn Objective<Solution = S, Distance = D> + Send + Sync>;
+
+/// Like `MultiObjective`, but owns it's objectives. It can be stored in
+/// structs, returned from functions and sent across threads.
+pub struct OwnedMultiObjective<S, D> {
+    pub objectives: Vec<BoxedObjective<S, D>>,
+}
+
+impl<S, D> OwnedMultiObjective<S, D> {
+    pub fn new(objectives: Vec<BoxedObjective<S, D>>) -> Self {
+        OwnedMultiObjective { objectives }
     }
+
+    /// Adds an objective.
+    pub fn with<O>(mut self, objective: O) -> Self
+    where
+        O: Objective<Solution = S, Distance = D> + Send + Sync + 'static,
+    {
+        self.objectives.push(Box::new(objective));
+        self
+    }
+}
+
+impl<S, D> Default for OwnedMultiObjective<S, D> {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl<S, D> ObjectiveSet for OwnedMultiObjective<S, D> {
+    type Distance = D;
+
+    fn num_objectives(&self) -> usize {
+        self.objectives.len()
+    }
+
+    fn objective(&self, i: usize) -> &dyn Objective<Solution = S, Distance = D> {
+        &*self.objectives[i]
+    }
+
+    fn is_pareto_dominance(&self) -> bool {
+        true
+    }
+}
+
+impl<S, D> DominationOrd for OwnedMultiObjective<S, D> {
+    type Solution = S;
+
+    fn domination_ord(&self, a: &Self::Solution, b: &Self::Solution) -> Ordering {
+        pareto_dominance(self, a, b)
+    }
+}
+
+#[test]
+fn test_objective_vector() {
+    use test_helper_objective::{Objective1, Objective2, Objective3, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2, &Objective3]);
+
+    assert_eq!(vec![-1.0, 1.0, 0.0], objective_vector(&mo, &Tuple(1, 2), &Tuple(2, 1)));
+    assert_eq!(vec![0.0, 0.0, 0.0], objective_vector(&mo, &Tuple(1, 2), &Tuple(1, 2)));
 }
 
 #[test]
@@ -78,4 +223,64 @@ fn test_multi_objective() {
 
     assert_eq!(Ordering::Less, mo.domination_ord(&a, &c));
     assert_eq!(Ordering::Greater, mo.domination_ord(&c, &a));
+
+    assert!(mo.is_pareto_dominance());
+}
+
+#[test]
+fn test_mixed_directions() {
+    use objective::{Directed, Objective};
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    // minimize the first, maximize the second value
+    let max2 = Directed::maximize(Objective2);
+    let objectives: [&dyn Objective<Solution = Tuple, Distance = f64>; 2] = [&Objective1, &max2];
+    let mo = MultiObjective::new(&objectives);
+
+    assert_eq!(Ordering::Less, mo.domination_ord(&Tuple(1, 3), &Tuple(1, 2)));
+    assert_eq!(Ordering::Less, mo.domination_ord(&Tuple(1, 3), &Tuple(2, 2)));
+    assert_eq!(Ordering::Equal, mo.domination_ord(&Tuple(1, 2), &Tuple(2, 3)));
+    assert_eq!(vec![-1.0, -1.0], objective_vector(&mo, &Tuple(1, 3), &Tuple(2, 2)));
+}
+
+#[test]
+fn test_owned_multi_objective() {
+    use std::thread;
+    use crowding_distance::assign_crowding_distance;
+    use non_dominated_sort::NonDominatedSort;
+    use objective::Directed;
+    use select_nsga::SelectNSGA;
+    use selection::SelectAndRank;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    fn build() -> OwnedMultiObjective<Tuple, f64> {
+        OwnedMultiObjective::default()
+            .with(Objective1)
+            .with(Directed::maximize(Objective2))
+    }
+
+    let mo = build();
+    assert_eq!(2, mo.num_objectives());
+    assert!(mo.is_pareto_dominance());
+    assert_eq!(Ordering::Less, mo.domination_ord(&Tuple(1, 3), &Tuple(2, 2)));
+    assert_eq!(Ordering::Equal, mo.domination_ord(&Tuple(1, 2), &Tuple(2, 3)));
+
+    let solutions = vec![Tuple(1, 1), Tuple(3, 3), Tuple(3, 1), Tuple(2, 2)];
+    let fronts = NonDominatedSort::new(&solutions, &mo).pareto_fronts();
+    let (crowding, _) = assign_crowding_distance(&fronts[0], &mo);
+    assert_eq!(3, crowding.len());
+
+    // select on another thread
+    let selected = thread::spawn(move || {
+        let mut selected: Vec<_> = SelectNSGA::default()
+            .select_and_rank(&solutions, 3, &mo)
+            .iter()
+            .map(|s| (s.index, s.rank))
+            .collect();
+        selected.sort();
+        selected
+    })
+    .join()
+    .unwrap();
+    assert_eq!(vec![(0, 0), (1, 0), (3, 0)], selected);
 }
@@ -0,0 +1,179 @@
+//! Ranking of solutions with invalid objective values.
+//!
+//! A single NaN fitness value would otherwise either panic in the total
+//! order of an objective, or make the dominance relation inconsistent.
+
+use std::cmp::Ordering;
+use objective::{Objective, ToF64};
+use multi_objective::ObjectiveSet;
+use non_dominated_sort::DominationOrd;
+
+/// Whether all objective values of `solution` are valid.
+///
+/// A value is invalid if `Objective::is_valid` says so, or if it's
+/// distance to itself is NaN. The objective values themselves are not
+/// accessible, but for an objective whose distance is the difference of
+/// the values, this is the case for NaN and for infinite values
+/// (`NaN - NaN` and `inf - inf`), whereas it is 0 for any finite value.
+/// Hence, infinite values count as invalid, too. For other distances,
+/// implement `Objective::is_valid`.
+pub fn is_valid<M>(multi_objective: &M, solution: &M::Solution) -> bool
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    invalid_objective(multi_objective, solution).is_none()
+}
+
+/// The first objective, in which `solution` has an invalid value.
+pub(crate) fn invalid_objective<M>(multi_objective: &M, solution: &M::Solution) -> Option<usize>
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    (0..multi_objective.num_objectives()).find(|&i| {
+        let objective = multi_objective.objective(i);
+        !objective.is_valid(solution) || objective.distance(solution, solution).to_f64().is_nan()
+    })
+}
+
+/// Wraps an objective set, so that solutions with invalid objective
+/// values (see `is_valid`) are worse than any valid solution.
+///
+/// Valid solutions are compared by the wrapped objectives. Invalid
+/// solutions are dominated by all valid solutions, and are equal to
+/// each other in dominance and in each objective. Hence, they form the
+/// last fronts, and the wrapped objectives are never called to order an
+/// invalid solution.
+pub struct NanWorst<'m, M>
+where
+    M: ObjectiveSet + 'm,
+{
+    objectives: Vec<NanWorstObjective<'m, M>>,
+    multi_objective: &'m M,
+}
+
+struct NanWorstObjective<'m, M>
+where
+    M: ObjectiveSet + 'm,
+{
+    multi_objective: &'m M,
+    index: usize,
+}
+
+impl<'m, M> NanWorst<'m, M>
+where
+    M: ObjectiveSet + 'm,
+    M::Distance: ToF64,
+{
+    pub fn new(multi_objective: &'m M) -> Self {
+        NanWorst {
+            objectives: (0..multi_objective.num_objectives())
+                .map(|index| NanWorstObjective {
+                    multi_objective,
+                    index,
+                })
+                .collect(),
+            multi_objective,
+        }
+    }
+}
+
+/// Orders a valid before an invalid solution.
+fn validity_ord(a_valid: bool, b_valid: bool) -> Option<Ordering> {
+    match (a_valid, b_valid) {
+        (true, true) => None,
+        (true, false) => Some(Ordering::Less),
+        (false, true) => Some(Ordering::Greater),
+        (false, false) => Some(Ordering::Equal),
+    }
+}
+
+impl<'m, M> Objective for NanWorstObjective<'m, M>
+where
+    M: ObjectiveSet + 'm,
+    M::Distance: ToF64,
+{
+    type Solution = M::Solution;
+    type Distance = f64;
+
+    fn total_order(&self, a: &M::Solution, b: &M::Solution) -> Ordering {
+        let valid = |s| is_valid(self.multi_objective, s);
+        validity_ord(valid(a), valid(b))
+            .unwrap_or_else(|| self.multi_objective.objective(self.index).total_order(a, b))
+    }
+
+    /// 0 if any of the solutions is invalid.
+    fn distance(&self, a: &M::Solution, b: &M::Solution) -> f64 {
+        if is_valid(self.multi_objective, a) && is_valid(self.multi_objective, b) {
+            self.multi_objective.objective(self.index).distance(a, b).to_f64()
+        } else {
+            0.0
+        }
+    }
+
+    /// A solution is invalid in all objectives if it is invalid in any
+    /// of the wrapped objectives, as the distance does not tell.
+    fn is_valid(&self, solution: &M::Solution) -> bool {
+        is_valid(self.multi_objective, solution)
+    }
+}
+
+impl<'m, M> ObjectiveSet for NanWorst<'m, M>
+where
+    M: ObjectiveSet + 'm,
+    M::Distance: ToF64,
+{
+    type Distance = f64;
+
+    fn num_objectives(&self) -> usize {
+        self.objectives.len()
+    }
+
+    fn objective(&self, i: usize) -> &dyn Objective<Solution = M::Solution, Distance = f64> {
+        &self.objectives[i]
+    }
+}
+
+impl<'m, M> DominationOrd for NanWorst<'m, M>
+where
+    M: ObjectiveSet + 'm,
+    M::Distance: ToF64,
+{
+    type Solution = M::Solution;
+
+    fn domination_ord(&self, a: &M::Solution, b: &M::Solution) -> Ordering {
+        let valid = |s| is_valid(self.multi_objective, s);
+        validity_ord(valid(a), valid(b)).unwrap_or_else(|| self.multi_objective.domination_ord(a, b))
+    }
+}
+
+#[test]
+fn test_nan_worst() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+
+    assert!(is_valid(&mo, &vec![1.0, -2.0]));
+    assert!(!is_valid(&mo, &vec![1.0, f64::NAN]));
+    assert!(!is_valid(&mo, &vec![f64::INFINITY, 1.0]));
+    assert_eq!(Some(1), invalid_objective(&mo, &vec![1.0, f64::NAN]));
+
+    // `Coordinate` would panic on NaN.
+    let nan_worst = NanWorst::new(&mo);
+    assert!(!nan_worst.is_pareto_dominance());
+    assert!(!nan_worst.objective(0).is_valid(&vec![1.0, f64::NAN]));
+    assert!(!is_valid(&nan_worst, &vec![1.0, f64::NAN]));
+    assert!(is_valid(&nan_worst, &vec![1.0, 2.0]));
+    assert_eq!(Ordering::Equal, nan_worst.domination_ord(&vec![1.0, 2.0], &vec![2.0, 1.0]));
+    assert_eq!(Ordering::Less, nan_worst.domination_ord(&vec![9.0, 9.0], &vec![0.0, f64::NAN]));
+    assert_eq!(Ordering::Greater, nan_worst.domination_ord(&vec![f64::NAN, 0.0], &vec![9.0, 9.0]));
+    assert_eq!(Ordering::Equal, nan_worst.domination_ord(&vec![f64::NAN, 0.0], &vec![0.0, f64::NAN]));
+
+    let objective = nan_worst.objective(0);
+    assert_eq!(Ordering::Greater, objective.total_order(&vec![0.0, f64::NAN], &vec![1.0, 1.0]));
+    assert_eq!(0.0, objective.distance(&vec![0.0, f64::NAN], &vec![1.0, 1.0]));
+    assert_eq!(-1.0, objective.distance(&vec![0.0, 0.0], &vec![1.0, 1.0]));
+}
@@ -1,4 +1,5 @@
 use std::cmp::Ordering;
+use std::ops::Neg;
 
 /// An *objective* defines a *total ordering relation* and a *distance
 /// metric* on a set of `solutions`. Given any two solutions, an
@@ -20,19 +21,20 @@ use std::cmp::Ordering;
 /// less an implementation detail or that of an optimization. Nothing
 /// prevents you from using the fitness value here as the solution
 /// value.
-
 pub trait Objective {
     /// The solution value type that we define the objective on.
     type Solution;
 
-    /// The output type of the distance metric.
+    /// The output type of the distance metric. Selection and the
+    /// indicators require it to implement `ToF64`.
     type Distance: Sized;
 
     /// An objective defines a total ordering between any two solution
     /// values.
     ///
     /// This answers the question, is solution `a` better, equal or
-    /// worse than solution `b`, according to the objective.
+    /// worse than solution `b`, according to the objective. `Less`
+    /// always means better. Use `Directed` to maximize an objective.
     fn total_order(&self, a: &Self::Solution, b: &Self::Solution) -> Ordering;
 
     /// An objective defines a distance metric between any two solution
@@ -47,6 +49,137 @@ pub trait Objective {
     /// Note: Distance values can be negative, i.e. the caller is
     /// responsible for obtaining absolute values.
     fn distance(&self, a: &Self::Solution, b: &Self::Solution) -> Self::Distance;
+
+    /// Whether the value of `solution` according to the objective is
+    /// valid, e.g. not NaN. An invalid value is worse than any valid
+    /// value in either direction (see `Directed`), and `NanWorst` ranks
+    /// solutions with an invalid value last.
+    ///
+    /// The default is `true`. Override it if `total_order` does not put
+    /// invalid values last by itself, or if the distance is not the
+    /// difference of the values (see `nan_worst::is_valid`).
+    fn is_valid(&self, _solution: &Self::Solution) -> bool {
+        true
+    }
+}
+
+/// A distance value which can be converted to `f64`, e.g. to calculate
+/// the crowding distance or an indicator.
+///
+/// Unlike `Into<f64>`, it is implemented for all primitive numeric
+/// types, including `i64`, `u64` and `usize`, for which the conversion
+/// can lose precision. For other types, e.g. fixed-point numbers,
+/// implement it along with the type.
+pub trait ToF64 {
+    fn to_f64(&self) -> f64;
+}
+
+macro_rules! to_f64_as {
+    ($($t:ty),+) => {
+        $(
+            impl ToF64 for $t {
+                fn to_f64(&self) -> f64 {
+                    *self as f64
+                }
+            }
+        )+
+    };
+}
+
+to_f64_as!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+/// Compares two objective values, of which the lesser is better. NaN is
+/// treated as the worst value, i.e. it is greater than any number and
+/// equal to NaN. Use this to implement `total_order` on floating point
+/// values without panicking on NaN.
+pub fn nan_worst_cmp(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (false, false) => a.partial_cmp(&b).unwrap(),
+        (false, true) => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        (true, true) => Ordering::Equal,
+    }
+}
+
+/// Whether smaller or larger values of an objective are better.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    #[default]
+    Minimize,
+    Maximize,
+}
+
+impl Direction {
+    /// Turns the `ordering` of two values into the ordering of the
+    /// solutions, i.e. reverses it when maximizing.
+    pub fn order(self, ordering: Ordering) -> Ordering {
+        match self {
+            Direction::Minimize => ordering,
+            Direction::Maximize => ordering.reverse(),
+        }
+    }
+}
+
+/// Wraps an objective to be minimized or maximized.
+///
+/// The total order of an objective always means "less is better". To
+/// maximize an objective, the order is reversed and the distance is
+/// negated. Everything else, e.g. the dominance of a `MultiObjective`,
+/// the crowding distance and the indicators, only relies on the total
+/// order and the signed distance, and hence respects the direction.
+pub struct Directed<O> {
+    pub objective: O,
+    pub direction: Direction,
+}
+
+impl<O> Directed<O> {
+    pub fn new(objective: O, direction: Direction) -> Self {
+        Directed {
+            objective,
+            direction,
+        }
+    }
+
+    pub fn minimize(objective: O) -> Self {
+        Self::new(objective, Direction::Minimize)
+    }
+
+    pub fn maximize(objective: O) -> Self {
+        Self::new(objective, Direction::Maximize)
+    }
+}
+
+impl<O> Objective for Directed<O>
+where
+    O: Objective,
+    O::Distance: Neg<Output = O::Distance>,
+{
+    type Solution = O::Solution;
+    type Distance = O::Distance;
+
+    /// Invalid values stay the worst, they are not reversed when
+    /// maximizing.
+    fn total_order(&self, a: &Self::Solution, b: &Self::Solution) -> Ordering {
+        match (self.objective.is_valid(a), self.objective.is_valid(b)) {
+            (true, true) => self.direction.order(self.objective.total_order(a, b)),
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => Ordering::Equal,
+        }
+    }
+
+    fn distance(&self, a: &Self::Solution, b: &Self::Solution) -> Self::Distance {
+        let distance = self.objective.distance(a, b);
+        match self.direction {
+            Direction::Minimize => distance,
+            Direction::Maximize => -distance,
+        }
+    }
+
+    fn is_valid(&self, solution: &Self::Solution) -> bool {
+        self.objective.is_valid(solution)
+    }
 }
 
 #[test]
@@ -63,3 +196,40 @@ fn test_objectives() {
     assert_eq!(1.0, Objective2.distance(a, b));
     assert_eq!(0.0, Objective3.distance(a, b));
 }
+
+#[test]
+fn test_nan_worst_cmp() {
+    assert_eq!(Ordering::Less, nan_worst_cmp(1.0, 2.0));
+    assert_eq!(Ordering::Less, nan_worst_cmp(f64::INFINITY, f64::NAN));
+    assert_eq!(Ordering::Greater, nan_worst_cmp(f64::NAN, f64::NEG_INFINITY));
+    assert_eq!(Ordering::Equal, nan_worst_cmp(f64::NAN, f64::NAN));
+}
+
+#[test]
+fn test_directed() {
+    use test_helper_objective::{Objective1, Tuple};
+
+    let a = &Tuple(1, 2);
+    let b = &Tuple(2, 1);
+
+    let min = Directed::minimize(Objective1);
+    assert_eq!(Ordering::Less, min.total_order(a, b));
+    assert_eq!(-1.0, min.distance(a, b));
+
+    let max = Directed::maximize(Objective1);
+    assert_eq!(Ordering::Greater, max.total_order(a, b));
+    assert_eq!(1.0, max.distance(a, b));
+}
+
+#[test]
+fn test_directed_nan() {
+    use test_helper_objective::Coordinate;
+
+    // NaN stays the worst value when maximizing.
+    let max = Directed::maximize(Coordinate(0));
+    assert_eq!(Ordering::Less, max.total_order(&vec![2.0], &vec![1.0]));
+    assert_eq!(Ordering::Greater, max.total_order(&vec![f64::NAN], &vec![1.0]));
+    assert_eq!(Ordering::Less, max.total_order(&vec![1.0], &vec![f64::NAN]));
+    assert_eq!(Ordering::Equal, max.total_order(&vec![f64::NAN], &vec![f64::NAN]));
+    assert!(!max.is_valid(&vec![f64::NAN]));
+}
@@ -0,0 +1,310 @@
+//! Observation of evolution runs.
+//!
+//! An `Observer` is called by `engine::run_observed` after the initial
+//! population is created and after each generation, with statistics of
+//! the ranked population. This allows to log or track the convergence
+//! of a run over time.
+
+use std::io::{self, Write};
+use std::time::Duration;
+use non_dominated_sort::{Front, SolutionWithIndex};
+use engine::Individual;
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use crowding_distance::{assign_crowding_distance, ObjectiveStat};
+use nan_worst::NanWorst;
+use indicator::hypervolume::hypervolume;
+
+/// Statistics of the ranked population after a generation.
+#[derive(Debug)]
+pub struct GenerationStats<'a, G: 'a, F: 'a> {
+    /// The number of generations performed. 0 for the initial
+    /// population.
+    pub generation: usize,
+
+    /// The number of fitness evaluations performed so far.
+    pub evaluations: usize,
+
+    /// The ranked population.
+    pub population: &'a [Individual<G, F>],
+
+    /// The spread of each objective within the first front, as returned
+    /// by `assign_crowding_distance`. Invalid objective values (see
+    /// `nan_worst::is_valid`) are treated as by `NanWorst`.
+    pub objective_stats: Vec<ObjectiveStat>,
+
+    /// The number of individuals of each rank, starting with rank 0.
+    pub front_sizes: Vec<usize>,
+
+    /// The time spent for this generation.
+    pub generation_time: Duration,
+
+    /// The time spent since the start of the run.
+    pub total_time: Duration,
+}
+
+impl<'a, G, F> GenerationStats<'a, G, F> {
+    pub fn new<M>(
+        generation: usize,
+        evaluations: usize,
+        population: &'a [Individual<G, F>],
+        multi_objective: &M,
+        generation_time: Duration,
+        total_time: Duration,
+    ) -> Self
+    where
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let mut front_sizes = Vec::new();
+        for individual in population {
+            if individual.rank >= front_sizes.len() {
+                front_sizes.resize(individual.rank + 1, 0);
+            }
+            front_sizes[individual.rank] += 1;
+        }
+
+        let objective_stats = if population.is_empty() {
+            Vec::new()
+        } else {
+            let front = Front {
+                rank: 0,
+                solutions: first_front(population)
+                    .enumerate()
+                    .map(|(index, individual)| SolutionWithIndex {
+                        solution: &individual.fitness,
+                        index,
+                    })
+                    .collect(),
+            };
+            // the first front can consist of invalid solutions only
+            assign_crowding_distance(&front, &NanWorst::new(multi_objective)).1
+        };
+
+        GenerationStats {
+            generation,
+            evaluations,
+            population,
+            objective_stats,
+            front_sizes,
+            generation_time,
+            total_time,
+        }
+    }
+
+    /// The individuals of rank 0.
+    pub fn first_front(&self) -> impl Iterator<Item = &'a Individual<G, F>> {
+        first_front(self.population)
+    }
+}
+
+fn first_front<G, F>(population: &[Individual<G, F>]) -> impl Iterator<Item = &Individual<G, F>> {
+    population.iter().filter(|individual| individual.rank == 0)
+}
+
+/// Observes an evolution run.
+pub trait Observer<G, F> {
+    /// Called with the statistics of the ranked population after each
+    /// generation, including the initial population.
+    fn observe(&mut self, stats: &GenerationStats<G, F>);
+}
+
+/// Observes nothing.
+impl<G, F> Observer<G, F> for () {
+    fn observe(&mut self, _stats: &GenerationStats<G, F>) {}
+}
+
+impl<G, F, O> Observer<G, F> for &mut O
+where
+    O: Observer<G, F> + ?Sized,
+{
+    fn observe(&mut self, stats: &GenerationStats<G, F>) {
+        (**self).observe(stats)
+    }
+}
+
+/// Calls both observers, in order.
+impl<G, F, A, B> Observer<G, F> for (A, B)
+where
+    A: Observer<G, F>,
+    B: Observer<G, F>,
+{
+    fn observe(&mut self, stats: &GenerationStats<G, F>) {
+        self.0.observe(stats);
+        self.1.observe(stats);
+    }
+}
+
+/// Writes one line of comma-separated values per generation: the
+/// generation, the total time in seconds, the number of fronts, the
+/// size of the first front and the spread of each objective. The first
+/// line is a header.
+///
+/// Writing stops at the first I/O error, which is returned by `finish`.
+pub struct CsvObserver<W: Write> {
+    writer: W,
+    header_written: bool,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> CsvObserver<W> {
+    pub fn new(writer: W) -> Self {
+        CsvObserver {
+            writer,
+            header_written: false,
+            error: None,
+        }
+    }
+
+    /// Flushes and returns the writer, or the first error that occurred.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_stats<G, F>(&mut self, stats: &GenerationStats<G, F>) -> io::Result<()> {
+        if !self.header_written {
+            write!(self.writer, "generation,time,fronts,first_front")?;
+            for i in 0..stats.objective_stats.len() {
+                write!(self.writer, ",spread_{}", i)?;
+            }
+            writeln!(self.writer)?;
+            self.header_written = true;
+        }
+
+        let time = stats.total_time.as_secs() as f64 + f64::from(stats.total_time.subsec_nanos()) * 1e-9;
+        write!(
+            self.writer,
+            "{},{:.6},{},{}",
+            stats.generation,
+            time,
+            stats.front_sizes.len(),
+            stats.front_sizes.first().cloned().unwrap_or(0)
+        )?;
+        for stat in stats.objective_stats.iter() {
+            write!(self.writer, ",{}", stat.spread)?;
+        }
+        writeln!(self.writer)
+    }
+}
+
+impl<G, F, W: Write> Observer<G, F> for CsvObserver<W> {
+    fn observe(&mut self, stats: &GenerationStats<G, F>) {
+        if self.error.is_none() {
+            if let Err(error) = self.write_stats(stats) {
+                self.error = Some(error);
+            }
+        }
+    }
+}
+
+/// Records the hypervolume of the first front with respect to
+/// `reference` after each generation (see `indicator::hypervolume`).
+pub struct HypervolumeObserver<'m, M>
+where
+    M: ObjectiveSet + 'm,
+{
+    pub multi_objective: &'m M,
+    pub reference: M::Solution,
+    /// The hypervolume after each generation, starting with the initial
+    /// population.
+    pub history: Vec<f64>,
+}
+
+impl<'m, M> HypervolumeObserver<'m, M>
+where
+    M: ObjectiveSet + 'm,
+    M::Distance: ToF64,
+{
+    pub fn new(multi_objective: &'m M, reference: M::Solution) -> Self {
+        HypervolumeObserver {
+            multi_objective,
+            reference,
+            history: Vec::new(),
+        }
+    }
+}
+
+impl<'m, G, M> Observer<G, M::Solution> for HypervolumeObserver<'m, M>
+where
+    M: ObjectiveSet + 'm,
+    M::Distance: ToF64,
+    M::Solution: Clone,
+{
+    fn observe(&mut self, stats: &GenerationStats<G, M::Solution>) {
+        let front: Vec<_> = stats.first_front().map(|i| i.fitness.clone()).collect();
+        self.history
+            .push(hypervolume(self.multi_objective, &front, &self.reference));
+    }
+}
+
+#[test]
+fn test_generation_stats() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let individual = |fitness: Tuple, rank: usize| Individual {
+        genome: (),
+        fitness,
+        rank,
+        crowding_distance: 0.0,
+    };
+    let population = vec![
+        individual(Tuple(1, 5), 0),
+        individual(Tuple(4, 2), 0),
+        individual(Tuple(5, 5), 1),
+        individual(Tuple(2, 3), 0),
+        individual(Tuple(6, 6), 2),
+    ];
+
+    let stats = GenerationStats::new(3, 25, &population, &mo, Duration::from_millis(10), Duration::from_secs(1));
+    assert_eq!(3, stats.generation);
+    assert_eq!(25, stats.evaluations);
+    assert_eq!(vec![3, 1, 1], stats.front_sizes);
+    assert_eq!(
+        vec![ObjectiveStat { spread: 3.0 }, ObjectiveStat { spread: 3.0 }],
+        stats.objective_stats
+    );
+    assert_eq!(3, stats.first_front().count());
+}
+
+#[test]
+fn test_observers() {
+    use multi_objective::MultiObjective;
+    use objective::Objective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+    let individual = |fitness: Point, rank: usize| Individual {
+        genome: (),
+        fitness,
+        rank,
+        crowding_distance: 0.0,
+    };
+
+    let mut csv = CsvObserver::new(Vec::new());
+    let mut hv = HypervolumeObserver::new(&mo, vec![4.0, 4.0]);
+
+    let population = vec![individual(vec![1.0, 3.0], 0), individual(vec![3.0, 3.5], 1)];
+    let stats = GenerationStats::new(0, 2, &population, &mo, Duration::from_secs(1), Duration::from_secs(1));
+    (&mut csv, &mut hv).observe(&stats);
+
+    let population = vec![individual(vec![1.0, 3.0], 0), individual(vec![3.0, 1.0], 0)];
+    let stats = GenerationStats::new(1, 4, &population, &mo, Duration::from_millis(500), Duration::from_millis(1500));
+    (&mut csv, &mut hv).observe(&stats);
+
+    assert_eq!(vec![3.0, 5.0], hv.history);
+
+    let csv = String::from_utf8(csv.finish().unwrap()).unwrap();
+    assert_eq!(
+        "generation,time,fronts,first_front,spread_0,spread_1\n\
+         0,1.000000,2,1,0,0\n\
+         1,1.500000,1,2,2,2\n",
+        csv
+    );
+}
@@ -0,0 +1,115 @@
+//! Operators for bit-string genomes.
+//!
+//! The crossover operators only exchange genes between the parents and
+//! hence work for any gene type, not only `bool`.
+
+use rand::Rng;
+
+/// Uniform crossover. Each gene is exchanged between the two children
+/// with probability 0.5.
+pub fn uniform_crossover<R: Rng, T: Clone>(rng: &mut R, parents: (&[T], &[T])) -> (Vec<T>, Vec<T>) {
+    assert_eq!(parents.0.len(), parents.1.len());
+
+    parents
+        .0
+        .iter()
+        .zip(parents.1.iter())
+        .map(|(a, b)| {
+            if rng.gen() {
+                (b.clone(), a.clone())
+            } else {
+                (a.clone(), b.clone())
+            }
+        })
+        .unzip()
+}
+
+/// N-point crossover. Chooses `n` distinct cut points and exchanges
+/// every other segment between the two children. `n` is limited to the
+/// number of possible cut points (`len - 1`).
+pub fn n_point_crossover<R: Rng, T: Clone>(rng: &mut R, parents: (&[T], &[T]), n: usize) -> (Vec<T>, Vec<T>) {
+    assert_eq!(parents.0.len(), parents.1.len());
+    let len = parents.0.len();
+
+    // Choose `n` distinct cut points out of 1..len using a partial
+    // Fisher-Yates shuffle.
+    let mut cut_points: Vec<usize> = (1..len).collect();
+    let n = n.min(cut_points.len());
+    for i in 0..n {
+        let j = rng.gen_range(i, cut_points.len());
+        cut_points.swap(i, j);
+    }
+    cut_points.truncate(n);
+    cut_points.sort();
+
+    let mut c1 = Vec::with_capacity(len);
+    let mut c2 = Vec::with_capacity(len);
+    let mut swapped = false;
+    let mut next_cut = cut_points.iter().peekable();
+
+    for i in 0..len {
+        if next_cut.peek() == Some(&&i) {
+            next_cut.next();
+            swapped = !swapped;
+        }
+        let (a, b) = if swapped {
+            (&parents.1[i], &parents.0[i])
+        } else {
+            (&parents.0[i], &parents.1[i])
+        };
+        c1.push(a.clone());
+        c2.push(b.clone());
+    }
+
+    (c1, c2)
+}
+
+/// Bit-flip mutation. Each bit is flipped with probability
+/// `probability`.
+pub fn bit_flip_mutation<R: Rng>(rng: &mut R, x: &mut [bool], probability: f64) {
+    for bit in x.iter_mut() {
+        if rng.gen::<f64>() < probability {
+            *bit = !*bit;
+        }
+    }
+}
+
+#[test]
+fn test_binary_crossover() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let p1 = vec![false; 10];
+    let p2 = vec![true; 10];
+
+    for _ in 0..100 {
+        // Each gene of the children comes from exactly one parent.
+        let (c1, c2) = uniform_crossover(&mut rng, (&p1, &p2));
+        assert!(c1.iter().zip(c2.iter()).all(|(a, b)| a != b));
+
+        // Two cut points result in three segments.
+        let (c1, c2) = n_point_crossover(&mut rng, (&p1, &p2), 2);
+        assert!(c1.iter().zip(c2.iter()).all(|(a, b)| a != b));
+        let changes = c1.windows(2).filter(|w| w[0] != w[1]).count();
+        assert_eq!(2, changes);
+        assert!(!c1[0]);
+    }
+
+    // More cut points than possible
+    let (c1, _) = n_point_crossover(&mut rng, (&p1, &p2), 20);
+    assert_eq!(vec![false, true, false, true, false, true, false, true, false, true], c1);
+}
+
+#[test]
+fn test_bit_flip_mutation() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut x = vec![false; 10];
+
+    bit_flip_mutation(&mut rng, &mut x, 0.0);
+    assert_eq!(vec![false; 10], x);
+
+    bit_flip_mutation(&mut rng, &mut x, 1.0);
+    assert_eq!(vec![true; 10], x);
+}
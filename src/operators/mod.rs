@@ -0,0 +1,10 @@
+//! Variation operators (crossover and mutation) for common genome
+//! representations.
+//!
+//! All operators draw their random numbers from the `Rng` passed in,
+//! so that results are reproducible given a seeded random number
+//! generator.
+
+pub mod real;
+pub mod binary;
+pub mod permutation;
@@ -0,0 +1,252 @@
+//! Operators for permutation genomes.
+//!
+//! A permutation genome of length `n` contains each of the values
+//! `0..n` exactly once. The crossover operators produce one child and
+//! preserve this property. The mutation operators work on any slice.
+
+use rand::Rng;
+
+/// Chooses a random segment `i..j` with `i < j <= len`.
+fn random_segment<R: Rng>(rng: &mut R, len: usize) -> (usize, usize) {
+    assert!(len > 0);
+    let a = rng.gen_range(0, len + 1);
+    let b = rng.gen_range(0, len + 1);
+    if a < b {
+        (a, b)
+    } else if a > b {
+        (b, a)
+    } else if a < len {
+        (a, a + 1)
+    } else {
+        (a - 1, a)
+    }
+}
+
+fn check_parents(parents: (&[usize], &[usize])) -> usize {
+    let n = parents.0.len();
+    assert_eq!(n, parents.1.len());
+    debug_assert!(is_permutation(parents.0));
+    debug_assert!(is_permutation(parents.1));
+    n
+}
+
+/// Returns true if `x` contains each of the values `0..x.len()` exactly
+/// once.
+pub fn is_permutation(x: &[usize]) -> bool {
+    let mut seen = vec![false; x.len()];
+    for &v in x.iter() {
+        if v >= x.len() || seen[v] {
+            return false;
+        }
+        seen[v] = true;
+    }
+    true
+}
+
+/// Partially mapped crossover (PMX) of Goldberg and Lingle.
+///
+/// The child inherits a random segment from the first parent. The
+/// remaining positions are taken from the second parent, where
+/// conflicting values are resolved by following the mapping defined by
+/// the segment.
+pub fn pmx<R: Rng>(rng: &mut R, parents: (&[usize], &[usize])) -> Vec<usize> {
+    let n = check_parents(parents);
+    if n == 0 {
+        return Vec::new();
+    }
+    let (p1, p2) = parents;
+    let (i, j) = random_segment(rng, n);
+
+    let mut position_in_p2 = vec![0; n];
+    for (pos, &v) in p2.iter().enumerate() {
+        position_in_p2[v] = pos;
+    }
+
+    let mut child: Vec<Option<usize>> = vec![None; n];
+    let mut taken = vec![false; n];
+    for k in i..j {
+        child[k] = Some(p1[k]);
+        taken[p1[k]] = true;
+    }
+
+    for (k, &v) in p2.iter().enumerate().take(j).skip(i) {
+        if taken[v] {
+            continue;
+        }
+        // Follow the mapping until we leave the segment.
+        let mut m = k;
+        while m >= i && m < j {
+            m = position_in_p2[p1[m]];
+        }
+        child[m] = Some(v);
+        taken[v] = true;
+    }
+
+    child
+        .into_iter()
+        .zip(p2.iter())
+        .map(|(c, &v)| c.unwrap_or(v))
+        .collect()
+}
+
+/// Order crossover (OX) of Davis.
+///
+/// The child inherits a random segment from the first parent. The
+/// remaining positions are filled, starting after the segment, with the
+/// missing values in the order in which they appear in the second
+/// parent, starting after the segment.
+pub fn order_crossover<R: Rng>(rng: &mut R, parents: (&[usize], &[usize])) -> Vec<usize> {
+    let n = check_parents(parents);
+    if n == 0 {
+        return Vec::new();
+    }
+    let (p1, p2) = parents;
+    let (i, j) = random_segment(rng, n);
+
+    let mut child = vec![0; n];
+    let mut taken = vec![false; n];
+    for k in i..j {
+        child[k] = p1[k];
+        taken[p1[k]] = true;
+    }
+
+    let mut fill_positions = (j..n).chain(0..i);
+    for k in (j..n).chain(0..j) {
+        let v = p2[k];
+        if !taken[v] {
+            child[fill_positions.next().unwrap()] = v;
+        }
+    }
+
+    debug_assert!(is_permutation(&child));
+    child
+}
+
+/// Cycle crossover (CX) of Oliver, Smith and Holland.
+///
+/// The positions are partitioned into cycles. The child inherits the
+/// values of the first cycle from the first parent, the values of the
+/// second cycle from the second parent, and so on. Each value is
+/// inherited at a position where one of the parents has it.
+pub fn cycle_crossover(parents: (&[usize], &[usize])) -> Vec<usize> {
+    let n = check_parents(parents);
+    let (p1, p2) = parents;
+
+    let mut position_in_p1 = vec![0; n];
+    for (pos, &v) in p1.iter().enumerate() {
+        position_in_p1[v] = pos;
+    }
+
+    let mut child = vec![0; n];
+    let mut visited = vec![false; n];
+    let mut from_first = true;
+
+    for start in 0..n {
+        if visited[start] {
+            continue;
+        }
+        let mut k = start;
+        while !visited[k] {
+            visited[k] = true;
+            child[k] = if from_first { p1[k] } else { p2[k] };
+            k = position_in_p1[p2[k]];
+        }
+        from_first = !from_first;
+    }
+
+    debug_assert!(is_permutation(&child));
+    child
+}
+
+/// Swap mutation. Exchanges two randomly chosen positions.
+pub fn swap_mutation<R: Rng, T>(rng: &mut R, x: &mut [T]) {
+    if x.len() < 2 {
+        return;
+    }
+    let a = rng.gen_range(0, x.len());
+    let b = rng.gen_range(0, x.len());
+    x.swap(a, b);
+}
+
+/// Inversion mutation. Reverses a randomly chosen segment.
+pub fn inversion_mutation<R: Rng, T>(rng: &mut R, x: &mut [T]) {
+    if x.len() < 2 {
+        return;
+    }
+    let (i, j) = random_segment(rng, x.len());
+    x[i..j].reverse();
+}
+
+/// Scramble mutation. Shuffles a randomly chosen segment.
+pub fn scramble_mutation<R: Rng, T>(rng: &mut R, x: &mut [T]) {
+    if x.len() < 2 {
+        return;
+    }
+    let (i, j) = random_segment(rng, x.len());
+    rng.shuffle(&mut x[i..j]);
+}
+
+#[test]
+fn test_permutation_crossover() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut p1: Vec<usize> = (0..10).collect();
+    let mut p2: Vec<usize> = (0..10).collect();
+
+    for _ in 0..100 {
+        rng.shuffle(&mut p1);
+        rng.shuffle(&mut p2);
+
+        assert!(is_permutation(&pmx(&mut rng, (&p1, &p2))));
+
+        assert!(is_permutation(&order_crossover(&mut rng, (&p1, &p2))));
+
+        // Each value is inherited at the position of one of the parents.
+        let c = cycle_crossover((&p1, &p2));
+        assert!(is_permutation(&c));
+        assert!(c.iter().enumerate().all(|(k, &v)| v == p1[k] || v == p2[k]));
+    }
+}
+
+#[test]
+fn test_cycle_crossover() {
+    // Cycles: positions {0, 3, 6, 7}, {1, 4} and {2, 5}.
+    let p1 = vec![0, 1, 2, 3, 4, 5, 6, 7];
+    let p2 = vec![3, 4, 5, 6, 1, 2, 7, 0];
+    assert_eq!(vec![0, 4, 2, 3, 1, 5, 6, 7], cycle_crossover((&p1, &p2)));
+}
+
+#[test]
+fn test_pmx() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let p1 = vec![0, 1, 2, 3, 4, 5, 6, 7];
+    let p2 = vec![7, 6, 5, 4, 3, 2, 1, 0];
+
+    // Whatever the segment, each value is inherited at the position of
+    // one of the parents, as the mapping is a reflection.
+    for _ in 0..100 {
+        let c = pmx(&mut rng, (&p1, &p2));
+        assert!(is_permutation(&c));
+        assert!(c.iter().enumerate().all(|(k, &v)| v == p1[k] || v == p2[k]));
+    }
+}
+
+#[test]
+fn test_permutation_mutation() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let mut x: Vec<usize> = (0..10).collect();
+
+    for _ in 0..100 {
+        swap_mutation(&mut rng, &mut x);
+        assert!(is_permutation(&x));
+        inversion_mutation(&mut rng, &mut x);
+        assert!(is_permutation(&x));
+        scramble_mutation(&mut rng, &mut x);
+        assert!(is_permutation(&x));
+    }
+}
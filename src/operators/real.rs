@@ -0,0 +1,310 @@
+//! Operators for real-valued genomes.
+//!
+//! Each variable `x[i]` of a genome is bounded by `bounds[i]`, a pair
+//! `(lower, upper)` with `lower <= upper`. All operators produce values
+//! within these bounds, either by construction (SBX, polynomial
+//! mutation) or by clamping the result to the bounds.
+
+use rand::Rng;
+use rand::distributions::{IndependentSample, Normal};
+
+/// The lower and upper bound of a real-valued variable.
+pub type Bounds = (f64, f64);
+
+/// Clamps `x` into `bounds`.
+#[inline]
+pub fn clamp(x: f64, bounds: Bounds) -> f64 {
+    debug_assert!(bounds.0 <= bounds.1);
+    x.max(bounds.0).min(bounds.1)
+}
+
+fn sbx_beta(u: f64, eta: f64) -> f64 {
+    debug_assert!((0.0..1.0).contains(&u));
+
+    if u <= 0.5 {
+        2.0 * u
+    } else {
+        1.0 / (2.0 * (1.0 - u))
+    }.powf(1.0 / (eta + 1.0))
+}
+
+fn sbx_beta_bounded(u: f64, eta: f64, gamma: f64) -> f64 {
+    debug_assert!((0.0..1.0).contains(&u));
+
+    let g = 1.0 - gamma;
+    let ug = u * g;
+
+    if u <= 0.5 / g {
+        2.0 * ug
+    } else {
+        1.0 / (2.0 * (1.0 - ug))
+    }.powf(1.0 / (eta + 1.0))
+}
+
+/// Simulated binary crossover (SBX) of a single unbounded variable.
+///
+/// `eta` is the distribution index. Large values create children close
+/// to their parents.
+pub fn sbx_single_var<R: Rng>(rng: &mut R, p: (f64, f64), eta: f64) -> (f64, f64) {
+    let u = rng.gen::<f64>();
+    let beta = sbx_beta(u, eta);
+
+    (
+        0.5 * (((1.0 + beta) * p.0) + ((1.0 - beta) * p.1)),
+        0.5 * (((1.0 - beta) * p.0) + ((1.0 + beta) * p.1)),
+    )
+}
+
+fn _sbx_single_var_bounded<R: Rng>(rng: &mut R, p: (f64, f64), bounds: Bounds, eta: f64) -> (f64, f64) {
+    let (a, b) = bounds;
+    let p_diff = p.1 - p.0;
+
+    debug_assert!(a <= b);
+    debug_assert!(p_diff > 0.0);
+    debug_assert!(p.0 >= a && p.0 <= b);
+    debug_assert!(p.1 >= a && p.1 <= b);
+
+    let beta_a = 1.0 + (p.0 - a) / p_diff;
+    let beta_b = 1.0 + (b - p.1) / p_diff;
+
+    fn gamma(beta: f64, eta: f64) -> f64 {
+        1.0 / (2.0 * beta.powf(eta + 1.0))
+    }
+
+    let gamma_a = gamma(beta_a, eta);
+    let gamma_b = gamma(beta_b, eta);
+
+    let u = rng.gen::<f64>();
+    let beta_ua = sbx_beta_bounded(u, eta, gamma_a);
+    let beta_ub = sbx_beta_bounded(u, eta, gamma_b);
+
+    let c = (
+        0.5 * (((1.0 + beta_ua) * p.0) + ((1.0 - beta_ua) * p.1)),
+        0.5 * (((1.0 - beta_ub) * p.0) + ((1.0 + beta_ub) * p.1)),
+    );
+
+    // guard against rounding errors
+    (clamp(c.0, bounds), clamp(c.1, bounds))
+}
+
+/// Simulated binary crossover (SBX) of a single variable, bounded by
+/// `bounds`. Both parents must lie within `bounds`.
+///
+/// `eta` is the distribution index. Large values create children close
+/// to their parents.
+pub fn sbx_single_var_bounded<R: Rng>(rng: &mut R, p: (f64, f64), bounds: Bounds, eta: f64) -> (f64, f64) {
+    if p.0 < p.1 {
+        _sbx_single_var_bounded(rng, (p.0, p.1), bounds, eta)
+    } else if p.0 > p.1 {
+        let r = _sbx_single_var_bounded(rng, (p.1, p.0), bounds, eta);
+        (r.1, r.0)
+    } else {
+        debug_assert!(p.0 == p.1);
+        (p.0, p.1)
+    }
+}
+
+/// Bounded simulated binary crossover (SBX) of two genomes. Applies
+/// `sbx_single_var_bounded` to each pair of variables and returns both
+/// children.
+pub fn sbx_crossover<R: Rng>(
+    rng: &mut R,
+    parents: (&[f64], &[f64]),
+    bounds: &[Bounds],
+    eta: f64,
+) -> (Vec<f64>, Vec<f64>) {
+    assert_eq!(parents.0.len(), parents.1.len());
+    assert_eq!(parents.0.len(), bounds.len());
+
+    parents
+        .0
+        .iter()
+        .zip(parents.1.iter())
+        .zip(bounds.iter())
+        .map(|((&x1, &x2), &b)| sbx_single_var_bounded(rng, (x1, x2), b, eta))
+        .unzip()
+}
+
+/// BLX-alpha crossover. Each variable of the child is drawn uniformly
+/// from the interval spanned by the parents, extended by `alpha` times
+/// it's length on both sides, and clamped to `bounds`.
+pub fn blx_alpha<R: Rng>(rng: &mut R, parents: (&[f64], &[f64]), bounds: &[Bounds], alpha: f64) -> Vec<f64> {
+    assert_eq!(parents.0.len(), parents.1.len());
+    assert_eq!(parents.0.len(), bounds.len());
+    debug_assert!(alpha >= 0.0);
+
+    parents
+        .0
+        .iter()
+        .zip(parents.1.iter())
+        .zip(bounds.iter())
+        .map(|((&x1, &x2), &b)| {
+            let (lo, hi) = if x1 < x2 { (x1, x2) } else { (x2, x1) };
+            let extend = alpha * (hi - lo);
+            let (lo, hi) = (lo - extend, hi + extend);
+            clamp(lo + rng.gen::<f64>() * (hi - lo), b)
+        })
+        .collect()
+}
+
+/// Differential evolution crossover (DE/rand/1/bin).
+///
+/// The mutant vector is `a + f * (b - c)`. Each variable of the child
+/// is taken from the mutant with probability `cr`, otherwise from
+/// `target`. At least one variable is always taken from the mutant. The
+/// result is clamped to `bounds`.
+pub fn de_crossover<R: Rng>(
+    rng: &mut R,
+    target: &[f64],
+    donors: (&[f64], &[f64], &[f64]),
+    bounds: &[Bounds],
+    f: f64,
+    cr: f64,
+) -> Vec<f64> {
+    let (a, b, c) = donors;
+    let n = target.len();
+    assert!(n > 0);
+    assert!(a.len() == n && b.len() == n && c.len() == n);
+    assert_eq!(n, bounds.len());
+
+    let j_rand = rng.gen_range(0, n);
+
+    (0..n)
+        .map(|j| {
+            if j == j_rand || rng.gen::<f64>() < cr {
+                clamp(a[j] + f * (b[j] - c[j]), bounds[j])
+            } else {
+                target[j]
+            }
+        })
+        .collect()
+}
+
+/// Polynomial mutation of Deb and Goyal. Each variable is mutated with
+/// probability `probability`. `eta` is the distribution index. Large
+/// values create mutants close to the original value.
+pub fn polynomial_mutation<R: Rng>(rng: &mut R, x: &mut [f64], bounds: &[Bounds], eta: f64, probability: f64) {
+    assert_eq!(x.len(), bounds.len());
+
+    let mut_pow = 1.0 / (eta + 1.0);
+
+    for (xi, &(lower, upper)) in x.iter_mut().zip(bounds.iter()) {
+        if rng.gen::<f64>() >= probability || upper <= lower {
+            continue;
+        }
+
+        let y = *xi;
+        let delta1 = (y - lower) / (upper - lower);
+        let delta2 = (upper - y) / (upper - lower);
+        let r = rng.gen::<f64>();
+
+        let deltaq = if r <= 0.5 {
+            let xy = 1.0 - delta1;
+            let val = 2.0 * r + (1.0 - 2.0 * r) * xy.powf(eta + 1.0);
+            val.powf(mut_pow) - 1.0
+        } else {
+            let xy = 1.0 - delta2;
+            let val = 2.0 * (1.0 - r) + 2.0 * (r - 0.5) * xy.powf(eta + 1.0);
+            1.0 - val.powf(mut_pow)
+        };
+
+        *xi = clamp(y + deltaq * (upper - lower), (lower, upper));
+    }
+}
+
+/// Uniform mutation. Each variable is replaced by a value drawn
+/// uniformly from it's bounds with probability `probability`.
+pub fn uniform_mutation<R: Rng>(rng: &mut R, x: &mut [f64], bounds: &[Bounds], probability: f64) {
+    assert_eq!(x.len(), bounds.len());
+
+    for (xi, &(lower, upper)) in x.iter_mut().zip(bounds.iter()) {
+        if rng.gen::<f64>() < probability {
+            *xi = lower + rng.gen::<f64>() * (upper - lower);
+        }
+    }
+}
+
+/// Gaussian mutation. With probability `probability`, normally
+/// distributed noise with standard deviation `sigma` is added to each
+/// variable. The result is clamped to `bounds`.
+pub fn gaussian_mutation<R: Rng>(rng: &mut R, x: &mut [f64], bounds: &[Bounds], sigma: f64, probability: f64) {
+    assert_eq!(x.len(), bounds.len());
+
+    let normal = Normal::new(0.0, sigma);
+
+    for (xi, &b) in x.iter_mut().zip(bounds.iter()) {
+        if rng.gen::<f64>() < probability {
+            *xi = clamp(*xi + normal.ind_sample(rng), b);
+        }
+    }
+}
+
+#[cfg(test)]
+fn assert_within(x: &[f64], bounds: &[Bounds]) {
+    for (&xi, &(lower, upper)) in x.iter().zip(bounds.iter()) {
+        assert!(xi >= lower && xi <= upper);
+    }
+}
+
+#[test]
+fn test_crossover_within_bounds() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let bounds = vec![(0.0, 1.0), (-5.0, 5.0), (2.0, 2.0)];
+    let p1 = vec![0.0, -4.0, 2.0];
+    let p2 = vec![1.0, 4.5, 2.0];
+    let p3 = vec![0.5, 0.0, 2.0];
+
+    for _ in 0..1000 {
+        let (c1, c2) = sbx_crossover(&mut rng, (&p1, &p2), &bounds, 2.0);
+        assert_within(&c1, &bounds);
+        assert_within(&c2, &bounds);
+
+        assert_within(&blx_alpha(&mut rng, (&p1, &p2), &bounds, 0.5), &bounds);
+        assert_within(&de_crossover(&mut rng, &p1, (&p1, &p2, &p3), &bounds, 0.8, 0.9), &bounds);
+    }
+}
+
+#[test]
+fn test_mutation_within_bounds() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let bounds = vec![(0.0, 1.0), (-5.0, 5.0), (2.0, 2.0)];
+
+    for _ in 0..1000 {
+        let mut x = vec![0.0, 5.0, 2.0];
+        polynomial_mutation(&mut rng, &mut x, &bounds, 20.0, 1.0);
+        assert_within(&x, &bounds);
+        uniform_mutation(&mut rng, &mut x, &bounds, 0.5);
+        assert_within(&x, &bounds);
+        gaussian_mutation(&mut rng, &mut x, &bounds, 1.0, 1.0);
+        assert_within(&x, &bounds);
+    }
+
+    // A probability of zero does not mutate.
+    let mut x = vec![0.5, 1.0, 2.0];
+    polynomial_mutation(&mut rng, &mut x, &bounds, 20.0, 0.0);
+    gaussian_mutation(&mut rng, &mut x, &bounds, 1.0, 0.0);
+    assert_eq!(vec![0.5, 1.0, 2.0], x);
+}
+
+#[test]
+fn test_operators_reproducible() {
+    use rand::{SeedableRng, XorShiftRng};
+
+    let bounds = vec![(0.0, 1.0); 4];
+    let p1 = vec![0.1, 0.2, 0.3, 0.4];
+    let p2 = vec![0.9, 0.8, 0.7, 0.6];
+
+    let run = |seed| {
+        let mut rng = XorShiftRng::from_seed(seed);
+        let (mut c1, _) = sbx_crossover(&mut rng, (&p1, &p2), &bounds, 15.0);
+        polynomial_mutation(&mut rng, &mut c1, &bounds, 20.0, 0.5);
+        c1
+    };
+
+    assert_eq!(run([1, 2, 3, 4]), run([1, 2, 3, 4]));
+    assert!(run([1, 2, 3, 4]) != run([4, 3, 2, 1]));
+}
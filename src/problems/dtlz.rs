@@ -0,0 +1,495 @@
+//! The scalable DTLZ problems of Deb, Thiele, Laumanns and Zitzler
+//! (2002).
+//!
+//! A problem with `M` objectives and `n` variables has `M - 1` position
+//! variables, which determine the location on the pareto front, and
+//! `k = n - M + 1` distance variables, which determine the distance to
+//! the pareto front via `g`. All variables are within `[0, 1]`.
+
+use std::f64::consts::PI;
+use operators::real::Bounds;
+use problems::{grid, non_dominated, Problem};
+use select_nsga3::das_dennis_reference_points;
+
+fn check(num_objectives: usize, num_variables: usize) {
+    assert!(num_objectives >= 2);
+    assert!(num_variables >= num_objectives);
+}
+
+/// The `g` of DTLZ1 and DTLZ3 (Rastrigin like, many local fronts).
+fn g_multimodal(xm: &[f64]) -> f64 {
+    100.0
+        * (xm.len() as f64
+            + xm.iter()
+                .map(|x| (x - 0.5).powi(2) - (20.0 * PI * (x - 0.5)).cos())
+                .sum::<f64>())
+}
+
+/// The `g` of DTLZ2, DTLZ4 and DTLZ5 (sphere).
+fn g_sphere(xm: &[f64]) -> f64 {
+    xm.iter().map(|x| (x - 0.5).powi(2)).sum()
+}
+
+/// The linear front of DTLZ1, `sum(f) = 0.5 * (1 + g)`.
+fn linear_objectives(position: &[f64], g: f64, num_objectives: usize) -> Vec<f64> {
+    (0..num_objectives)
+        .map(|i| {
+            let mut f = 0.5 * (1.0 + g);
+            for x in position[..num_objectives - 1 - i].iter() {
+                f *= x;
+            }
+            if i > 0 {
+                f *= 1.0 - position[num_objectives - 1 - i];
+            }
+            f
+        })
+        .collect()
+}
+
+/// The spherical front of DTLZ2-6, `sum(f^2) = (1 + g)^2`. The
+/// position is given as angles `theta`, scaled to `[0, 1]`.
+fn spherical_objectives(theta: &[f64], g: f64, num_objectives: usize) -> Vec<f64> {
+    (0..num_objectives)
+        .map(|i| {
+            let mut f = 1.0 + g;
+            for t in theta[..num_objectives - 1 - i].iter() {
+                f *= (t * PI / 2.0).cos();
+            }
+            if i > 0 {
+                f *= (theta[num_objectives - 1 - i] * PI / 2.0).sin();
+            }
+            f
+        })
+        .collect()
+}
+
+/// The angles of DTLZ5 and DTLZ6. All but the first angle degenerate to
+/// `0.5` on the pareto front, which hence is a curve.
+fn degenerate_theta(position: &[f64], g: f64) -> Vec<f64> {
+    position
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            if i == 0 {
+                x
+            } else {
+                (1.0 + 2.0 * g * x) / (2.0 * (1.0 + g))
+            }
+        })
+        .collect()
+}
+
+fn optimal_variables(num_variables: usize, position: &[f64], distance: f64) -> Vec<f64> {
+    let mut x = position.to_vec();
+    x.resize(num_variables, distance);
+    x
+}
+
+/// The largest set of Das-Dennis points, not exceeding `n` points.
+fn simplex_points(num_objectives: usize, n: usize) -> Vec<Vec<f64>> {
+    let mut divisions = 1;
+    let mut points = das_dennis_reference_points(num_objectives, divisions);
+    loop {
+        let next = das_dennis_reference_points(num_objectives, divisions + 1);
+        if next.len() > n {
+            return points;
+        }
+        points = next;
+        divisions += 1;
+    }
+}
+
+/// DTLZ1 has a linear pareto front and `11^k - 1` local pareto fronts.
+pub struct Dtlz1 {
+    pub num_objectives: usize,
+    pub num_variables: usize,
+}
+
+impl Dtlz1 {
+    /// With the recommended `k = 5` distance variables.
+    pub fn new(num_objectives: usize) -> Self {
+        check(num_objectives, num_objectives + 4);
+        Dtlz1 {
+            num_objectives,
+            num_variables: num_objectives + 4,
+        }
+    }
+}
+
+impl Problem for Dtlz1 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let g = g_multimodal(&x[self.num_objectives - 1..]);
+        linear_objectives(x, g, self.num_objectives)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.num_variables, position, 0.5)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        simplex_points(self.num_objectives, n)
+            .into_iter()
+            .map(|w| w.into_iter().map(|wi| 0.5 * wi).collect())
+            .collect()
+    }
+}
+
+/// DTLZ2 has a spherical pareto front.
+pub struct Dtlz2 {
+    pub num_objectives: usize,
+    pub num_variables: usize,
+}
+
+impl Dtlz2 {
+    /// With the recommended `k = 10` distance variables.
+    pub fn new(num_objectives: usize) -> Self {
+        check(num_objectives, num_objectives + 9);
+        Dtlz2 {
+            num_objectives,
+            num_variables: num_objectives + 9,
+        }
+    }
+}
+
+/// Projects the Das-Dennis points onto the unit sphere.
+fn spherical_front(num_objectives: usize, n: usize) -> Vec<Vec<f64>> {
+    simplex_points(num_objectives, n)
+        .into_iter()
+        .map(|w| {
+            let norm = w.iter().map(|wi| wi * wi).sum::<f64>().sqrt();
+            w.into_iter().map(|wi| wi / norm).collect()
+        })
+        .collect()
+}
+
+impl Problem for Dtlz2 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let g = g_sphere(&x[self.num_objectives - 1..]);
+        spherical_objectives(x, g, self.num_objectives)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.num_variables, position, 0.5)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        spherical_front(self.num_objectives, n)
+    }
+}
+
+/// DTLZ3 has the spherical pareto front of DTLZ2 and `3^k - 1` local
+/// pareto fronts.
+pub struct Dtlz3 {
+    pub num_objectives: usize,
+    pub num_variables: usize,
+}
+
+impl Dtlz3 {
+    /// With the recommended `k = 10` distance variables.
+    pub fn new(num_objectives: usize) -> Self {
+        check(num_objectives, num_objectives + 9);
+        Dtlz3 {
+            num_objectives,
+            num_variables: num_objectives + 9,
+        }
+    }
+}
+
+impl Problem for Dtlz3 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let g = g_multimodal(&x[self.num_objectives - 1..]);
+        spherical_objectives(x, g, self.num_objectives)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.num_variables, position, 0.5)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        spherical_front(self.num_objectives, n)
+    }
+}
+
+/// DTLZ4 has the spherical pareto front of DTLZ2, but a biased density
+/// of solutions, controlled by `alpha`.
+pub struct Dtlz4 {
+    pub num_objectives: usize,
+    pub num_variables: usize,
+    pub alpha: f64,
+}
+
+impl Dtlz4 {
+    /// With the recommended `k = 10` distance variables and `alpha =
+    /// 100`.
+    pub fn new(num_objectives: usize) -> Self {
+        check(num_objectives, num_objectives + 9);
+        Dtlz4 {
+            num_objectives,
+            num_variables: num_objectives + 9,
+            alpha: 100.0,
+        }
+    }
+}
+
+impl Problem for Dtlz4 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let g = g_sphere(&x[self.num_objectives - 1..]);
+        let theta: Vec<f64> = x[..self.num_objectives - 1]
+            .iter()
+            .map(|xi| xi.powf(self.alpha))
+            .collect();
+        spherical_objectives(&theta, g, self.num_objectives)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.num_variables, position, 0.5)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        spherical_front(self.num_objectives, n)
+    }
+}
+
+/// DTLZ5 has a degenerate pareto front, a curve on the unit sphere.
+pub struct Dtlz5 {
+    pub num_objectives: usize,
+    pub num_variables: usize,
+}
+
+impl Dtlz5 {
+    /// With the recommended `k = 10` distance variables.
+    pub fn new(num_objectives: usize) -> Self {
+        check(num_objectives, num_objectives + 9);
+        Dtlz5 {
+            num_objectives,
+            num_variables: num_objectives + 9,
+        }
+    }
+}
+
+impl Problem for Dtlz5 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let g = g_sphere(&x[self.num_objectives - 1..]);
+        let theta = degenerate_theta(&x[..self.num_objectives - 1], g);
+        spherical_objectives(&theta, g, self.num_objectives)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.num_variables, position, 0.5)
+    }
+}
+
+/// DTLZ6 has the degenerate pareto front of DTLZ5, but a `g` that is
+/// harder to converge.
+pub struct Dtlz6 {
+    pub num_objectives: usize,
+    pub num_variables: usize,
+}
+
+impl Dtlz6 {
+    /// With the recommended `k = 10` distance variables.
+    pub fn new(num_objectives: usize) -> Self {
+        check(num_objectives, num_objectives + 9);
+        Dtlz6 {
+            num_objectives,
+            num_variables: num_objectives + 9,
+        }
+    }
+}
+
+impl Problem for Dtlz6 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let g: f64 = x[self.num_objectives - 1..].iter().map(|xi| xi.powf(0.1)).sum();
+        let theta = degenerate_theta(&x[..self.num_objectives - 1], g);
+        spherical_objectives(&theta, g, self.num_objectives)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.num_variables, position, 0.0)
+    }
+}
+
+/// DTLZ7 has `2^(M-1)` disconnected pareto optimal regions.
+pub struct Dtlz7 {
+    pub num_objectives: usize,
+    pub num_variables: usize,
+}
+
+impl Dtlz7 {
+    /// With the recommended `k = 20` distance variables.
+    pub fn new(num_objectives: usize) -> Self {
+        check(num_objectives, num_objectives + 19);
+        Dtlz7 {
+            num_objectives,
+            num_variables: num_objectives + 19,
+        }
+    }
+}
+
+impl Problem for Dtlz7 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let m = self.num_objectives;
+        let xm = &x[m - 1..];
+        let g = 1.0 + 9.0 / xm.len() as f64 * xm.iter().sum::<f64>();
+
+        let mut f: Vec<f64> = x[..m - 1].to_vec();
+        let h = m as f64
+            - f.iter()
+                .map(|fi| fi / (1.0 + g) * (1.0 + (3.0 * PI * fi).sin()))
+                .sum::<f64>();
+        f.push((1.0 + g) * h);
+        f
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.num_variables, position, 0.0)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        // The disconnected regions make up only a part of the curve or
+        // surface. Oversample, then remove the dominated points.
+        let points: Vec<_> = grid(self.num_objectives - 1, 4 * n)
+            .iter()
+            .map(|position| self.evaluate(&self.pareto_optimal_variables(position)))
+            .collect();
+        non_dominated(points)
+    }
+}
+
+#[test]
+fn test_dtlz_pareto_fronts() {
+    use problems::distance_to_front;
+
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+    for m in 2..5 {
+        let front = Dtlz1::new(m).pareto_front(100);
+        assert!(front.len() <= 100 && front.len() > 10);
+        for f in front.iter() {
+            assert!(close(0.5, f.iter().sum()));
+        }
+
+        let problems: Vec<Box<dyn Problem>> = vec![
+            Box::new(Dtlz2::new(m)),
+            Box::new(Dtlz3::new(m)),
+            Box::new(Dtlz4::new(m)),
+            Box::new(Dtlz5::new(m)),
+            Box::new(Dtlz6::new(m)),
+        ];
+        for problem in problems.iter() {
+            for f in problem.pareto_front(100).iter() {
+                assert!(close(1.0, f.iter().map(|fi| fi * fi).sum()));
+            }
+        }
+
+        let front = Dtlz7::new(m).pareto_front(100);
+        assert!(!front.is_empty());
+        for f in front.iter() {
+            assert!(f[..m - 1].iter().all(|&fi| (0.0..=1.0).contains(&fi)));
+        }
+    }
+
+    // The pareto optimal variables evaluate to points on the front.
+    let positions: Vec<Vec<f64>> = (0..11).map(|i| vec![i as f64 / 10.0]).collect();
+    assert!(distance_to_front(&Dtlz1::new(2), &positions, 1000) < 1e-3);
+    assert!(distance_to_front(&Dtlz2::new(2), &positions, 1000) < 1e-2);
+    assert!(distance_to_front(&Dtlz3::new(2), &positions, 1000) < 1e-2);
+}
+
+#[test]
+fn test_dtlz_evaluate() {
+    // Away from the optimum, g > 0.
+    let f = Dtlz2::new(2).evaluate(&[0.0; 11]);
+    assert!(close_to(&[1.0 + 2.5, 0.0], &f));
+
+    let f = Dtlz1::new(3).evaluate(&[1.0, 1.0, 0.5, 0.5, 0.5, 0.5, 0.5]);
+    assert!(close_to(&[0.5, 0.0, 0.0], &f));
+
+    fn close_to(expected: &[f64], actual: &[f64]) -> bool {
+        expected.iter().zip(actual.iter()).all(|(a, b)| (a - b).abs() < 1e-9)
+    }
+}
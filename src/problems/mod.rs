@@ -0,0 +1,133 @@
+//! Standard multi-objective benchmark problems.
+//!
+//! All problems are defined on real-valued decision vectors and all
+//! objectives are to be minimized. Besides evaluation, each problem
+//! knows it's pareto optimal set, which allows to sample the true
+//! pareto front, e.g. for use as the reference front of an indicator.
+
+use operators::real::Bounds;
+
+pub mod zdt;
+pub mod dtlz;
+pub mod wfg;
+
+pub trait Problem {
+    /// The number of decision variables.
+    fn num_variables(&self) -> usize;
+
+    /// The number of objectives.
+    fn num_objectives(&self) -> usize;
+
+    /// The bounds of each decision variable.
+    fn bounds(&self) -> Vec<Bounds>;
+
+    /// Evaluates the objective values of the decision vector `x`.
+    fn evaluate(&self, x: &[f64]) -> Vec<f64>;
+
+    /// Returns a pareto optimal decision vector.
+    ///
+    /// `position` has `num_objectives() - 1` components, each within
+    /// `[0, 1]`, and selects the solution within the pareto optimal set.
+    /// For problems with a disconnected pareto front, the returned
+    /// decision vector is only optimal with respect to it's distance
+    /// variables, i.e. it lies on the curve or surface that contains the
+    /// pareto front, but might be dominated.
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64>;
+
+    /// Samples approximately `n` points of the true pareto front.
+    ///
+    /// The default implementation evaluates the pareto optimal decision
+    /// vectors on a regular grid of positions and removes dominated and
+    /// duplicate points.
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        let points: Vec<_> = grid(self.num_objectives().saturating_sub(1), n)
+            .iter()
+            .map(|position| self.evaluate(&self.pareto_optimal_variables(position)))
+            .collect();
+        non_dominated(points)
+    }
+}
+
+/// A regular grid of at least `n` points in `[0, 1]^dimensions`.
+pub(crate) fn grid(dimensions: usize, n: usize) -> Vec<Vec<f64>> {
+    let per_dimension = if dimensions <= 1 {
+        n
+    } else {
+        (n as f64).powf(1.0 / dimensions as f64).ceil() as usize
+    };
+    let values: Vec<f64> = match per_dimension {
+        0 => Vec::new(),
+        1 => vec![0.0],
+        m => (0..m).map(|i| i as f64 / (m - 1) as f64).collect(),
+    };
+
+    let mut points = vec![Vec::with_capacity(dimensions)];
+    for _ in 0..dimensions {
+        points = points
+            .into_iter()
+            .flat_map(|p| {
+                values.iter().map(move |&v| {
+                    let mut p = p.clone();
+                    p.push(v);
+                    p
+                })
+            })
+            .collect();
+    }
+    points
+}
+
+/// Removes dominated and duplicate points (all objectives minimized).
+pub(crate) fn non_dominated(points: Vec<Vec<f64>>) -> Vec<Vec<f64>> {
+    let dominates = |a: &Vec<f64>, b: &Vec<f64>| {
+        a.iter().zip(b.iter()).all(|(x, y)| x <= y) && a.iter().zip(b.iter()).any(|(x, y)| x < y)
+    };
+
+    let mut result: Vec<Vec<f64>> = Vec::new();
+    for (i, p) in points.iter().enumerate() {
+        let dominated = points.iter().any(|q| dominates(q, p));
+        let duplicate = points[..i].iter().any(|q| q == p);
+        if !dominated && !duplicate {
+            result.push(p.clone());
+        }
+    }
+    result
+}
+
+/// For each of `positions`, the distance of the pareto optimal point to
+/// the nearest point of the sampled pareto front.
+#[cfg(test)]
+pub(crate) fn distance_to_front<P: Problem + ?Sized>(problem: &P, positions: &[Vec<f64>], n: usize) -> f64 {
+    let front = problem.pareto_front(n);
+    assert!(!front.is_empty());
+
+    positions
+        .iter()
+        .map(|position| {
+            let f = problem.evaluate(&problem.pareto_optimal_variables(position));
+            front
+                .iter()
+                .map(|q| {
+                    q.iter()
+                        .zip(f.iter())
+                        .map(|(a, b)| (a - b) * (a - b))
+                        .sum::<f64>()
+                        .sqrt()
+                })
+                .fold(f64::INFINITY, f64::min)
+        })
+        .fold(0.0, f64::max)
+}
+
+#[test]
+fn test_grid() {
+    assert_eq!(vec![vec![0.0], vec![0.5], vec![1.0]], grid(1, 3));
+    assert_eq!(9, grid(2, 5).len());
+    assert_eq!(vec![Vec::<f64>::new()], grid(0, 5));
+}
+
+#[test]
+fn test_non_dominated() {
+    let points = vec![vec![1.0, 2.0], vec![2.0, 2.0], vec![2.0, 1.0], vec![1.0, 2.0]];
+    assert_eq!(vec![vec![1.0, 2.0], vec![2.0, 1.0]], non_dominated(points));
+}
@@ -0,0 +1,697 @@
+//! The WFG toolkit problems of Huband, Hingston, Barone and While
+//! (2006).
+//!
+//! A problem with `M` objectives has `k` position variables and `l`
+//! distance variables. `k` has to be a multiple of `M - 1`. The `i`-th
+//! variable (starting at one) is within `[0, 2i]`.
+//!
+//! Each problem normalizes the variables, applies a sequence of
+//! transformations (shift, bias and reduction) to obtain `M` values,
+//! and maps these onto the objectives through a shape function. The
+//! objective `m` (starting at one) is scaled by `2m`.
+
+use std::f64::consts::PI;
+use operators::real::Bounds;
+use problems::{grid, non_dominated, Problem};
+
+const EPSILON: f64 = 1e-10;
+
+/// Clamps `x` to `[0, 1]`. Values within `EPSILON` of the bounds are
+/// snapped to them, as the strong polynomial bias of WFG1 would
+/// otherwise blow up round-off errors of the pareto optimal variables.
+fn correct_to_01(x: f64) -> f64 {
+    if x < EPSILON {
+        0.0
+    } else if x > 1.0 - EPSILON {
+        1.0
+    } else {
+        x
+    }
+}
+
+// ------------------------------------------------------------------
+// Transformations
+// ------------------------------------------------------------------
+
+fn s_linear(y: f64, a: f64) -> f64 {
+    correct_to_01((y - a).abs() / ((a - y).floor() + a).abs())
+}
+
+fn s_decept(y: f64, a: f64, b: f64, c: f64) -> f64 {
+    let tmp1 = (y - a + b).floor() * (1.0 - c + (a - b) / b) / (a - b);
+    let tmp2 = (a + b - y).floor() * (1.0 - c + (1.0 - a - b) / b) / (1.0 - a - b);
+    correct_to_01(1.0 + ((y - a).abs() - b) * (tmp1 + tmp2 + 1.0 / b))
+}
+
+fn s_multi(y: f64, a: f64, b: f64, c: f64) -> f64 {
+    let tmp1 = (y - c).abs() / (2.0 * ((c - y).floor() + c));
+    let tmp2 = (4.0 * a + 2.0) * PI * (0.5 - tmp1);
+    correct_to_01((1.0 + tmp2.cos() + 4.0 * b * tmp1 * tmp1) / (b + 2.0))
+}
+
+fn b_poly(y: f64, alpha: f64) -> f64 {
+    correct_to_01(y.powf(alpha))
+}
+
+fn b_flat(y: f64, a: f64, b: f64, c: f64) -> f64 {
+    let tmp1 = (y - b).floor().min(0.0) * a * (b - y) / b;
+    let tmp2 = (c - y).floor().min(0.0) * (1.0 - a) * (y - c) / (1.0 - c);
+    correct_to_01(a + tmp1 - tmp2)
+}
+
+/// The exponent of the parameter dependent bias `b_param` with the
+/// constants used by WFG7-9.
+fn b_param_exponent(u: f64) -> f64 {
+    let (a, b, c) = (0.98 / 49.98, 0.02, 50.0);
+    b + (c - b) * (a - (1.0 - 2.0 * u) * ((0.5 - u).floor() + a).abs())
+}
+
+fn b_param(y: f64, u: f64) -> f64 {
+    correct_to_01(y.powf(b_param_exponent(u)))
+}
+
+fn r_sum(y: &[f64], w: &[f64]) -> f64 {
+    let num: f64 = y.iter().zip(w.iter()).map(|(yi, wi)| yi * wi).sum();
+    let den: f64 = w.iter().sum();
+    correct_to_01(num / den)
+}
+
+fn mean(y: &[f64]) -> f64 {
+    y.iter().sum::<f64>() / y.len() as f64
+}
+
+fn r_nonsep(y: &[f64], a: usize) -> f64 {
+    let n = y.len();
+    let mut num = 0.0;
+    for j in 0..n {
+        num += y[j];
+        for k in 0..a - 1 {
+            num += (y[j] - y[(1 + j + k) % n]).abs();
+        }
+    }
+    let half = (a as f64 / 2.0).ceil();
+    let den = n as f64 / a as f64 * half * (1.0 + 2.0 * a as f64 - 2.0 * half);
+    correct_to_01(num / den)
+}
+
+/// Reduces the position variables group-wise to `M - 1` values, and
+/// the remaining variables to a single value, using a weighted sum.
+fn sum_reduction(y: &[f64], w: &[f64], k: usize, m: usize) -> Vec<f64> {
+    let gap = k / (m - 1);
+    let mut t: Vec<f64> = (0..m - 1)
+        .map(|i| r_sum(&y[i * gap..(i + 1) * gap], &w[i * gap..(i + 1) * gap]))
+        .collect();
+    t.push(r_sum(&y[k..], &w[k..]));
+    t
+}
+
+/// Like `sum_reduction`, but with uniform weights.
+fn uniform_sum_reduction(y: &[f64], k: usize, m: usize) -> Vec<f64> {
+    sum_reduction(y, &vec![1.0; y.len()], k, m)
+}
+
+/// Like `sum_reduction`, but using the non-separable reduction.
+fn nonsep_reduction(y: &[f64], k: usize, m: usize) -> Vec<f64> {
+    let gap = k / (m - 1);
+    let mut t: Vec<f64> = (0..m - 1)
+        .map(|i| r_nonsep(&y[i * gap..(i + 1) * gap], gap))
+        .collect();
+    t.push(r_nonsep(&y[k..], y.len() - k));
+    t
+}
+
+/// Reduces pairs of distance variables (WFG2 and WFG3).
+fn pairwise_nonsep(y: &[f64], k: usize) -> Vec<f64> {
+    let mut result = y[..k].to_vec();
+    result.extend(y[k..].chunks(2).map(|pair| r_nonsep(pair, 2)));
+    result
+}
+
+// ------------------------------------------------------------------
+// Shapes
+// ------------------------------------------------------------------
+
+#[derive(Clone, Copy)]
+enum Shape {
+    /// Convex, the last objective mixed (WFG1).
+    ConvexMixed,
+    /// Convex, the last objective disconnected (WFG2).
+    ConvexDisconnected,
+    /// Linear (WFG3).
+    Linear,
+    /// Concave (WFG4-9).
+    Concave,
+}
+
+/// The shape functions `h_1..h_M` for the position `x` (`M - 1`
+/// values).
+fn shape_values(x: &[f64], shape: Shape) -> Vec<f64> {
+    let p = x.len();
+
+    // The generic form of the linear, convex and concave shapes:
+    // `prod(first(x[..p - m])) * second(x[p - m])`.
+    let generic = |first: &dyn Fn(f64) -> f64, second: &dyn Fn(f64) -> f64| -> Vec<f64> {
+        (0..p + 1)
+            .map(|m| {
+                let mut h: f64 = x[..p - m].iter().map(|&xi| first(xi)).product();
+                if m > 0 {
+                    h *= second(x[p - m]);
+                }
+                h
+            })
+            .collect()
+    };
+
+    match shape {
+        Shape::Linear => generic(&|xi| xi, &|xi| 1.0 - xi),
+        Shape::Concave => generic(&|xi| (xi * PI / 2.0).sin(), &|xi| (xi * PI / 2.0).cos()),
+        Shape::ConvexMixed | Shape::ConvexDisconnected => {
+            let mut h = generic(&|xi| 1.0 - (xi * PI / 2.0).cos(), &|xi| 1.0 - (xi * PI / 2.0).sin());
+            let x0 = x[0];
+            h[p] = match shape {
+                Shape::ConvexMixed => {
+                    let aux = 2.0 * 5.0 * PI;
+                    1.0 - x0 - (aux * x0 + PI / 2.0).cos() / aux
+                }
+                _ => 1.0 - x0 * (5.0 * PI * x0).cos().powi(2),
+            };
+            h
+        }
+    }
+}
+
+/// Maps the transformed values `t` onto the objectives. If `degenerate`,
+/// all but the first position are fixed (WFG3).
+fn objectives(t: &[f64], degenerate: bool, shape: Shape) -> Vec<f64> {
+    let m = t.len();
+    let distance = t[m - 1];
+    let x: Vec<f64> = (0..m - 1)
+        .map(|i| {
+            let a = if degenerate && i > 0 { 0.0 } else { 1.0 };
+            distance.max(a) * (t[i] - 0.5) + 0.5
+        })
+        .collect();
+
+    shape_values(&x, shape)
+        .into_iter()
+        .enumerate()
+        .map(|(i, h)| distance + 2.0 * (i + 1) as f64 * h)
+        .collect()
+}
+
+/// Samples the pareto front directly from the shape function, with the
+/// distance at it's optimum of zero.
+fn front(num_objectives: usize, n: usize, degenerate: bool, shape: Shape) -> Vec<Vec<f64>> {
+    let points = grid(num_objectives - 1, n)
+        .into_iter()
+        .map(|mut t| {
+            t.push(0.0);
+            objectives(&t, degenerate, shape)
+        })
+        .collect();
+    non_dominated(points)
+}
+
+// ------------------------------------------------------------------
+// Decision variables
+// ------------------------------------------------------------------
+
+fn check(num_objectives: usize, k: usize, l: usize) {
+    assert!(num_objectives >= 2);
+    assert!(k > 0 && k % (num_objectives - 1) == 0);
+    assert!(l > 0);
+}
+
+fn bounds(n: usize) -> Vec<Bounds> {
+    (1..n + 1).map(|i| (0.0, 2.0 * i as f64)).collect()
+}
+
+fn normalize(z: &[f64]) -> Vec<f64> {
+    z.iter()
+        .enumerate()
+        .map(|(i, zi)| zi / (2.0 * (i + 1) as f64))
+        .collect()
+}
+
+fn denormalize(y: &[f64]) -> Vec<f64> {
+    y.iter()
+        .enumerate()
+        .map(|(i, yi)| yi * 2.0 * (i + 1) as f64)
+        .collect()
+}
+
+/// The normalized position variables. Each group of position variables
+/// is set to the corresponding component of `position`.
+fn position_variables(k: usize, num_objectives: usize, position: &[f64]) -> Vec<f64> {
+    let gap = k / (num_objectives - 1);
+    (0..k).map(|i| position[i / gap]).collect()
+}
+
+/// The pareto optimal variables of WFG1-7, with all distance variables
+/// at `0.35`.
+fn optimal_variables(k: usize, l: usize, num_objectives: usize, position: &[f64]) -> Vec<f64> {
+    let mut y = position_variables(k, num_objectives, position);
+    y.resize(k + l, 0.35);
+    denormalize(&y)
+}
+
+/// The parameters of a WFG problem.
+macro_rules! wfg_problem {
+    ($name:ident, $doc:expr) => {
+        #[doc = $doc]
+        pub struct $name {
+            pub num_objectives: usize,
+            /// The number of position variables.
+            pub k: usize,
+            /// The number of distance variables.
+            pub l: usize,
+        }
+
+        impl $name {
+            /// With `k = 2 * (M - 1)` position and `l = 20` distance
+            /// variables.
+            pub fn new(num_objectives: usize) -> Self {
+                assert!(num_objectives >= 2);
+                Self::with_variables(num_objectives, 2 * (num_objectives - 1), 20)
+            }
+
+            pub fn with_variables(num_objectives: usize, k: usize, l: usize) -> Self {
+                check(num_objectives, k, l);
+                $name { num_objectives, k, l }
+            }
+        }
+    };
+}
+
+wfg_problem!(Wfg1, "WFG1 has a convex and mixed pareto front and a strong bias towards a part of it.");
+wfg_problem!(Wfg2, "WFG2 has a convex and disconnected pareto front and non-separable variables. `l` must be even.");
+wfg_problem!(Wfg3, "WFG3 has a linear and degenerate pareto front. `l` must be even.");
+wfg_problem!(Wfg4, "WFG4 has a concave pareto front and is highly multi-modal.");
+wfg_problem!(Wfg5, "WFG5 has a concave pareto front and is deceptive.");
+wfg_problem!(Wfg6, "WFG6 has a concave pareto front and non-separable variables.");
+wfg_problem!(Wfg7, "WFG7 has a concave pareto front and a parameter dependent bias of the position variables.");
+wfg_problem!(Wfg8, "WFG8 has a concave pareto front and a parameter dependent bias of the distance variables.");
+wfg_problem!(Wfg9, "WFG9 has a concave pareto front, is deceptive, multi-modal and non-separable.");
+
+impl Problem for Wfg1 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        let k = self.k;
+        let y: Vec<f64> = normalize(z)
+            .into_iter()
+            .enumerate()
+            .map(|(i, yi)| if i < k { yi } else { b_flat(s_linear(yi, 0.35), 0.8, 0.75, 0.85) })
+            .map(|yi| b_poly(yi, 0.02))
+            .collect();
+        let w: Vec<f64> = (1..y.len() + 1).map(|i| 2.0 * i as f64).collect();
+        let t = sum_reduction(&y, &w, k, self.num_objectives);
+        objectives(&t, false, Shape::ConvexMixed)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.k, self.l, self.num_objectives, position)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        front(self.num_objectives, n, false, Shape::ConvexMixed)
+    }
+}
+
+impl Problem for Wfg2 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        assert!(self.l % 2 == 0);
+        let k = self.k;
+        let y: Vec<f64> = normalize(z)
+            .into_iter()
+            .enumerate()
+            .map(|(i, yi)| if i < k { yi } else { s_linear(yi, 0.35) })
+            .collect();
+        let y = pairwise_nonsep(&y, k);
+        let t = uniform_sum_reduction(&y, k, self.num_objectives);
+        objectives(&t, false, Shape::ConvexDisconnected)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.k, self.l, self.num_objectives, position)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        // Only parts of the shape are pareto optimal. Oversample.
+        front(self.num_objectives, 4 * n, false, Shape::ConvexDisconnected)
+    }
+}
+
+impl Problem for Wfg3 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        assert!(self.l % 2 == 0);
+        let k = self.k;
+        let y: Vec<f64> = normalize(z)
+            .into_iter()
+            .enumerate()
+            .map(|(i, yi)| if i < k { yi } else { s_linear(yi, 0.35) })
+            .collect();
+        let y = pairwise_nonsep(&y, k);
+        let t = uniform_sum_reduction(&y, k, self.num_objectives);
+        objectives(&t, true, Shape::Linear)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.k, self.l, self.num_objectives, position)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        front(self.num_objectives, n, true, Shape::Linear)
+    }
+}
+
+impl Problem for Wfg4 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        let y: Vec<f64> = normalize(z)
+            .into_iter()
+            .map(|yi| s_multi(yi, 30.0, 10.0, 0.35))
+            .collect();
+        let t = uniform_sum_reduction(&y, self.k, self.num_objectives);
+        objectives(&t, false, Shape::Concave)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.k, self.l, self.num_objectives, position)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        front(self.num_objectives, n, false, Shape::Concave)
+    }
+}
+
+impl Problem for Wfg5 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        let y: Vec<f64> = normalize(z)
+            .into_iter()
+            .map(|yi| s_decept(yi, 0.35, 0.001, 0.05))
+            .collect();
+        let t = uniform_sum_reduction(&y, self.k, self.num_objectives);
+        objectives(&t, false, Shape::Concave)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.k, self.l, self.num_objectives, position)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        front(self.num_objectives, n, false, Shape::Concave)
+    }
+}
+
+impl Problem for Wfg6 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        let k = self.k;
+        let y: Vec<f64> = normalize(z)
+            .into_iter()
+            .enumerate()
+            .map(|(i, yi)| if i < k { yi } else { s_linear(yi, 0.35) })
+            .collect();
+        let t = nonsep_reduction(&y, k, self.num_objectives);
+        objectives(&t, false, Shape::Concave)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.k, self.l, self.num_objectives, position)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        front(self.num_objectives, n, false, Shape::Concave)
+    }
+}
+
+impl Problem for Wfg7 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        let k = self.k;
+        let y = normalize(z);
+        let y: Vec<f64> = (0..y.len())
+            .map(|i| {
+                if i < k {
+                    b_param(y[i], mean(&y[i + 1..]))
+                } else {
+                    s_linear(y[i], 0.35)
+                }
+            })
+            .collect();
+        let t = uniform_sum_reduction(&y, k, self.num_objectives);
+        objectives(&t, false, Shape::Concave)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_variables(self.k, self.l, self.num_objectives, position)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        front(self.num_objectives, n, false, Shape::Concave)
+    }
+}
+
+impl Problem for Wfg8 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        let k = self.k;
+        let y = normalize(z);
+        let y: Vec<f64> = (0..y.len())
+            .map(|i| {
+                if i < k {
+                    y[i]
+                } else {
+                    s_linear(b_param(y[i], mean(&y[..i])), 0.35)
+                }
+            })
+            .collect();
+        let t = uniform_sum_reduction(&y, k, self.num_objectives);
+        objectives(&t, false, Shape::Concave)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        // Each distance variable has to be biased to 0.35, depending on
+        // the mean of all variables before it.
+        let mut y = position_variables(self.k, self.num_objectives, position);
+        for _ in 0..self.l {
+            let u = mean(&y);
+            y.push(0.35f64.powf(1.0 / b_param_exponent(u)));
+        }
+        denormalize(&y)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        front(self.num_objectives, n, false, Shape::Concave)
+    }
+}
+
+impl Problem for Wfg9 {
+    fn num_variables(&self) -> usize {
+        self.k + self.l
+    }
+
+    fn num_objectives(&self) -> usize {
+        self.num_objectives
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        bounds(self.k + self.l)
+    }
+
+    fn evaluate(&self, z: &[f64]) -> Vec<f64> {
+        let k = self.k;
+        let y = normalize(z);
+        let n = y.len();
+        let y: Vec<f64> = (0..n)
+            .map(|i| if i + 1 < n { b_param(y[i], mean(&y[i + 1..])) } else { y[i] })
+            .enumerate()
+            .map(|(i, yi)| {
+                if i < k {
+                    s_decept(yi, 0.35, 0.001, 0.05)
+                } else {
+                    s_multi(yi, 30.0, 95.0, 0.35)
+                }
+            })
+            .collect();
+        let t = nonsep_reduction(&y, k, self.num_objectives);
+        objectives(&t, false, Shape::Concave)
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        // Each but the last variable has to be biased to 0.35, depending
+        // on the mean of all variables after it.
+        let n = self.k + self.l;
+        let mut y = position_variables(self.k, self.num_objectives, position);
+        y.resize(n, 0.35);
+        for i in (self.k..n - 1).rev() {
+            let u = mean(&y[i + 1..]);
+            y[i] = 0.35f64.powf(1.0 / b_param_exponent(u));
+        }
+        denormalize(&y)
+    }
+
+    fn pareto_front(&self, n: usize) -> Vec<Vec<f64>> {
+        front(self.num_objectives, n, false, Shape::Concave)
+    }
+}
+
+#[test]
+fn test_wfg_pareto_optimal_variables() {
+    use problems::distance_to_front;
+
+    // Bi-objective: the pareto optimal variables evaluate to points on
+    // the sampled front.
+    let positions: Vec<Vec<f64>> = (0..11).map(|i| vec![i as f64 / 10.0]).collect();
+    let problems: Vec<Box<dyn Problem>> = vec![
+        Box::new(Wfg1::new(2)),
+        Box::new(Wfg3::new(2)),
+        Box::new(Wfg4::new(2)),
+        Box::new(Wfg5::new(2)),
+        Box::new(Wfg6::new(2)),
+        Box::new(Wfg7::new(2)),
+        Box::new(Wfg8::new(2)),
+        Box::new(Wfg9::new(2)),
+    ];
+    for problem in problems.iter() {
+        assert!(distance_to_front(&**problem, &positions, 2000) < 1e-2);
+    }
+
+    // Three objectives: the concave fronts are the sphere
+    // `sum((f_m / 2m)^2) = 1`.
+    let problems: Vec<Box<dyn Problem>> = vec![
+        Box::new(Wfg4::new(3)),
+        Box::new(Wfg5::new(3)),
+        Box::new(Wfg6::new(3)),
+        Box::new(Wfg7::new(3)),
+        Box::new(Wfg8::new(3)),
+        Box::new(Wfg9::new(3)),
+    ];
+    for problem in problems.iter() {
+        for position in grid(2, 20).iter() {
+            let f = problem.evaluate(&problem.pareto_optimal_variables(position));
+            let r: f64 = f.iter()
+                .enumerate()
+                .map(|(i, fi)| (fi / (2.0 * (i + 1) as f64)).powi(2))
+                .sum();
+            assert!((r - 1.0).abs() < 1e-6);
+        }
+        for f in problem.pareto_front(100).iter() {
+            assert_eq!(3, f.len());
+        }
+    }
+}
+
+#[test]
+fn test_wfg_pareto_fronts() {
+    // WFG1: f1 = 2 (1 - cos(x pi / 2)), the mixed f2 = 4 at x = 0.
+    let front = Wfg1::new(2).pareto_front(101);
+    assert_eq!(101, front.len());
+    assert!((front[0][1] - 4.0).abs() < 1e-9);
+    assert!((front[100][0] - 2.0).abs() < 1e-9);
+
+    // WFG2 is disconnected
+    let front = Wfg2::new(2).pareto_front(100);
+    assert!(!front.is_empty() && front.len() < 400);
+
+    // WFG3 is linear in two objectives, `f1 / 2 + f2 / 4 = 1`.
+    for f in Wfg3::new(2).pareto_front(50).iter() {
+        assert!((f[0] / 2.0 + f[1] / 4.0 - 1.0).abs() < 1e-9);
+    }
+
+    // Away from the pareto front
+    let problem = Wfg4::new(2);
+    let f = problem.evaluate(&vec![0.0; problem.num_variables()]);
+    assert!((f[0] / 2.0).powi(2) + (f[1] / 4.0).powi(2) > 1.0);
+}
@@ -0,0 +1,307 @@
+//! The bi-objective ZDT problems of Zitzler, Deb and Thiele (2000).
+//!
+//! Each problem has the form `f1 = f1(x1)`, `f2 = g(x2..xn) * h(f1, g)`.
+//! The pareto optimal set is reached for `g = 1`.
+
+use std::f64::consts::PI;
+use operators::real::Bounds;
+use problems::Problem;
+
+fn sum_rest(x: &[f64]) -> f64 {
+    x[1..].iter().sum()
+}
+
+/// The default `g` of ZDT1-3: `1 + 9 / (n - 1) * sum(x2..xn)`.
+fn g_linear(x: &[f64]) -> f64 {
+    debug_assert!(x.len() >= 2);
+    1.0 + 9.0 / (x.len() - 1) as f64 * sum_rest(x)
+}
+
+/// `x1` at `position`, the remaining variables at zero.
+fn optimal_at_zero(num_variables: usize, position: &[f64]) -> Vec<f64> {
+    let mut x = vec![0.0; num_variables];
+    x[0] = position[0];
+    x
+}
+
+/// ZDT1 has a convex pareto front `f2 = 1 - sqrt(f1)`.
+pub struct Zdt1 {
+    pub num_variables: usize,
+}
+
+impl Default for Zdt1 {
+    fn default() -> Self {
+        Zdt1 { num_variables: 30 }
+    }
+}
+
+impl Problem for Zdt1 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let f1 = x[0];
+        let g = g_linear(x);
+        vec![f1, g * (1.0 - (f1 / g).sqrt())]
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_at_zero(self.num_variables, position)
+    }
+}
+
+/// ZDT2 has a non-convex pareto front `f2 = 1 - f1^2`.
+pub struct Zdt2 {
+    pub num_variables: usize,
+}
+
+impl Default for Zdt2 {
+    fn default() -> Self {
+        Zdt2 { num_variables: 30 }
+    }
+}
+
+impl Problem for Zdt2 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let f1 = x[0];
+        let g = g_linear(x);
+        vec![f1, g * (1.0 - (f1 / g).powi(2))]
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_at_zero(self.num_variables, position)
+    }
+}
+
+/// ZDT3 has a pareto front consisting of five disconnected parts.
+pub struct Zdt3 {
+    pub num_variables: usize,
+}
+
+impl Default for Zdt3 {
+    fn default() -> Self {
+        Zdt3 { num_variables: 30 }
+    }
+}
+
+impl Problem for Zdt3 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let f1 = x[0];
+        let g = g_linear(x);
+        let h = 1.0 - (f1 / g).sqrt() - (f1 / g) * (10.0 * PI * f1).sin();
+        vec![f1, g * h]
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_at_zero(self.num_variables, position)
+    }
+}
+
+/// ZDT4 is multi-modal with `21^9` local pareto fronts. The pareto
+/// front is that of ZDT1.
+pub struct Zdt4 {
+    pub num_variables: usize,
+}
+
+impl Default for Zdt4 {
+    fn default() -> Self {
+        Zdt4 { num_variables: 10 }
+    }
+}
+
+impl Problem for Zdt4 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        let mut bounds = vec![(-5.0, 5.0); self.num_variables];
+        bounds[0] = (0.0, 1.0);
+        bounds
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let f1 = x[0];
+        let g = 1.0
+            + 10.0 * (x.len() - 1) as f64
+            + x[1..]
+                .iter()
+                .map(|xi| xi * xi - 10.0 * (4.0 * PI * xi).cos())
+                .sum::<f64>();
+        vec![f1, g * (1.0 - (f1 / g).sqrt())]
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_at_zero(self.num_variables, position)
+    }
+}
+
+/// ZDT5 is a deceptive binary problem.
+///
+/// The decision vector consists of 80 bits, a 30 bit substring followed
+/// by ten 5 bit substrings. Each bit is represented by a variable
+/// within `[0, 1]`. Values `>= 0.5` represent a one. The pareto front
+/// consists of the 31 points `f2 = 10 / f1` with `f1 = 1..31`.
+pub struct Zdt5;
+
+impl Zdt5 {
+    /// The number of ones in `bits`.
+    fn unitation(bits: &[f64]) -> usize {
+        bits.iter().filter(|&&b| b >= 0.5).count()
+    }
+}
+
+impl Problem for Zdt5 {
+    fn num_variables(&self) -> usize {
+        80
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); 80]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        assert_eq!(80, x.len());
+        let f1 = 1.0 + Zdt5::unitation(&x[..30]) as f64;
+        let g: f64 = x[30..]
+            .chunks(5)
+            .map(|substring| match Zdt5::unitation(substring) {
+                5 => 1.0,
+                u => 2.0 + u as f64,
+            })
+            .sum();
+        vec![f1, g / f1]
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        let ones = (position[0] * 30.0).round() as usize;
+        (0..80)
+            .map(|i| if i < ones || i >= 30 { 1.0 } else { 0.0 })
+            .collect()
+    }
+}
+
+/// ZDT6 has a non-convex pareto front `f2 = 1 - f1^2`, which is
+/// non-uniformly populated.
+pub struct Zdt6 {
+    pub num_variables: usize,
+}
+
+impl Default for Zdt6 {
+    fn default() -> Self {
+        Zdt6 { num_variables: 10 }
+    }
+}
+
+impl Problem for Zdt6 {
+    fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    fn num_objectives(&self) -> usize {
+        2
+    }
+
+    fn bounds(&self) -> Vec<Bounds> {
+        vec![(0.0, 1.0); self.num_variables]
+    }
+
+    fn evaluate(&self, x: &[f64]) -> Vec<f64> {
+        let f1 = 1.0 - (-4.0 * x[0]).exp() * (6.0 * PI * x[0]).sin().powi(6);
+        let g = 1.0 + 9.0 * (sum_rest(x) / (x.len() - 1) as f64).powf(0.25);
+        vec![f1, g * (1.0 - (f1 / g).powi(2))]
+    }
+
+    fn pareto_optimal_variables(&self, position: &[f64]) -> Vec<f64> {
+        optimal_at_zero(self.num_variables, position)
+    }
+}
+
+#[test]
+fn test_zdt_pareto_fronts() {
+    let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+    for f in Zdt1::default().pareto_front(100).iter() {
+        assert!(close(f[1], 1.0 - f[0].sqrt()));
+    }
+    for f in Zdt2::default().pareto_front(100).iter() {
+        assert!(close(f[1], 1.0 - f[0] * f[0]));
+    }
+    for f in Zdt4::default().pareto_front(100).iter() {
+        assert!(close(f[1], 1.0 - f[0].sqrt()));
+    }
+    for f in Zdt6::default().pareto_front(100).iter() {
+        assert!(close(f[1], 1.0 - f[0] * f[0]));
+        assert!(f[0] >= 0.28);
+    }
+
+    // The five disconnected parts of ZDT3
+    let front = Zdt3::default().pareto_front(1000);
+    let parts = front.windows(2).filter(|w| w[1][0] - w[0][0] > 0.01).count() + 1;
+    assert_eq!(5, parts);
+
+    let front = Zdt5.pareto_front(100);
+    assert_eq!(31, front.len());
+    for f in front.iter() {
+        assert!(close(f[1], 10.0 / f[0]));
+    }
+}
+
+#[test]
+fn test_zdt_evaluate() {
+    // A random point is not better than the pareto front.
+    let x = vec![0.25, 0.5, 0.5];
+    let f = Zdt1 { num_variables: 3 }.evaluate(&x);
+    assert_eq!(0.25, f[0]);
+    assert_eq!(5.5 * (1.0 - (0.25f64 / 5.5).sqrt()), f[1]);
+
+    let f = Zdt4 { num_variables: 3 }.evaluate(&[0.25, 1.0, -1.0]);
+    assert!((f[1] - 3.0 * (1.0 - (0.25f64 / 3.0).sqrt())).abs() < 1e-9);
+
+    let mut x = vec![0.0; 80];
+    for xi in x[..3].iter_mut() {
+        *xi = 1.0;
+    }
+    assert_eq!(vec![4.0, 20.0 / 4.0], Zdt5.evaluate(&x));
+}
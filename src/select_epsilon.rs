@@ -0,0 +1,241 @@
+use selection::SelectAndRank;
+use non_dominated_sort::{Front, SolutionWithIndex};
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use crowding_distance::{assign_crowding_distance, cmp_crowding_distance, AssignedCrowdingDistance};
+use epsilon_dominance::{epsilon_box_ord, is_rejected_by};
+use nan_worst::is_valid;
+use std::cmp::Ordering;
+
+/// Select `n` solutions in the style of the epsilon-MOEA.
+///
+/// The solutions are sorted into fronts by epsilon-box dominance (see
+/// `EpsilonBoxDominance`). The first front is the epsilon archive of all
+/// solutions, the second front the epsilon archive of the remaining
+/// solutions, and so on. Hence, each front contains at most one solution
+/// per box. The boxes have size `epsilons[i]` in the `i`-th objective and
+/// start at the best value of each objective among the solutions.
+///
+/// As with `SelectNSGA`, complete fronts are put into the result set,
+/// and the solutions of the front that does not fit completely are
+/// preferred by crowding distance.
+pub struct SelectEpsilon {
+    pub epsilons: Vec<f64>,
+}
+
+impl SelectAndRank for SelectEpsilon {
+    fn select_and_rank<'a, S: 'a, M>(
+        &self,
+        solutions: &'a [S],
+        n: usize,
+        multi_objective: &M,
+    ) -> Vec<AssignedCrowdingDistance<'a, S>>
+    where
+        M: ObjectiveSet<Solution = S>,
+        M::Distance: ToF64,
+    {
+        let num_objectives = multi_objective.num_objectives();
+        assert_eq!(num_objectives, self.epsilons.len());
+
+        // Cannot select more solutions than we actually have
+        let n = solutions.len().min(n);
+        if n == 0 {
+            return Vec::new();
+        }
+
+        // The box positions, relative to the best solution of each
+        // objective.
+        let best: Vec<&S> = (0..num_objectives)
+            .map(|i| {
+                let objective = multi_objective.objective(i);
+                solutions
+                    .iter()
+                    .min_by(|a, b| objective.total_order(a, b))
+                    .unwrap()
+            })
+            .collect();
+        // Invalid solutions (see `nan_worst::is_valid`) are positioned
+        // at infinity, behind all valid solutions.
+        let positions: Vec<Vec<f64>> = solutions
+            .iter()
+            .map(|solution| {
+                if !is_valid(multi_objective, solution) {
+                    return vec![f64::INFINITY; num_objectives];
+                }
+                (0..num_objectives)
+                    .map(|i| {
+                        let distance = multi_objective.objective(i).distance(solution, best[i]).to_f64();
+                        distance.abs() / self.epsilons[i]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut result = Vec::with_capacity(n);
+        let mut remaining: Vec<usize> = (0..solutions.len()).collect();
+        let mut rank = 0;
+
+        while result.len() < n {
+            let (front, rest) = epsilon_front(&remaining, solutions, &positions, multi_objective);
+            let front = Front {
+                rank,
+                solutions: front
+                    .into_iter()
+                    .map(|index| SolutionWithIndex {
+                        solution: &solutions[index],
+                        index,
+                    })
+                    .collect(),
+            };
+            let (mut assigned_crowding, _) = assign_crowding_distance(&front, multi_objective);
+
+            let missing_solutions = n - result.len();
+            if assigned_crowding.len() > missing_solutions {
+                assigned_crowding.sort_by(|a, b| cmp_crowding_distance(a.crowding_distance, b.crowding_distance));
+            }
+            result.extend(assigned_crowding.into_iter().take(missing_solutions));
+
+            remaining = rest;
+            rank += 1;
+        }
+
+        debug_assert_eq!(n, result.len());
+
+        result
+    }
+}
+
+/// Splits the solutions `indices` into their epsilon archive and the
+/// remaining solutions.
+fn epsilon_front<S, M>(
+    indices: &[usize],
+    solutions: &[S],
+    positions: &[Vec<f64>],
+    multi_objective: &M,
+) -> (Vec<usize>, Vec<usize>)
+where
+    M: ObjectiveSet<Solution = S>,
+{
+    let mut front: Vec<usize> = Vec::new();
+    let mut rest = Vec::new();
+
+    for &candidate in indices.iter() {
+        let rejected = front.iter().any(|&member| {
+            let pareto = multi_objective.domination_ord(&solutions[member], &solutions[candidate]);
+            is_rejected_by(&positions[candidate], &positions[member], pareto)
+        });
+        if rejected {
+            rest.push(candidate);
+            continue;
+        }
+
+        let (evicted, kept): (Vec<usize>, Vec<usize>) = front.into_iter().partition(|&member| {
+            let pareto = multi_objective.domination_ord(&solutions[candidate], &solutions[member]);
+            epsilon_box_ord(&positions[candidate], &positions[member], pareto) == Ordering::Less
+        });
+        rest.extend(evicted);
+        front = kept;
+        front.push(candidate);
+    }
+
+    rest.sort();
+    (front, rest)
+}
+
+#[test]
+fn test_select_epsilon() {
+    use objective::Objective;
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+
+    // All solutions are pareto optimal. 0, 1 and 2 share a box, and 1
+    // is closest to it's corner, then 2. The box of 5 is dominated.
+    let solutions = vec![
+        vec![0.2, 3.9],
+        vec![0.5, 3.2],
+        vec![0.9, 3.05],
+        vec![2.0, 2.0],
+        vec![3.5, 0.0],
+        vec![0.0, 4.5],
+    ];
+    let selection = SelectEpsilon {
+        epsilons: vec![1.0, 1.0],
+    };
+
+    let selected = selection.select_and_rank(&solutions, 4, &mo);
+    let mut indices: Vec<_> = selected.iter().map(|s| (s.index, s.rank)).collect();
+    indices.sort();
+    assert_eq!(vec![(1, 0), (2, 1), (3, 0), (4, 0)], indices);
+
+    assert_eq!(6, selection.select_and_rank(&solutions, 10, &mo).len());
+}
+
+#[test]
+fn test_select_epsilon_shared_box() {
+    use objective::Objective;
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+
+    // 2, 3 and 4 share the box (2, 2). 4 dominates 2, and is closer to
+    // the corner than 3. 2 and 3 are equally close to the corner, so
+    // that 2, which is considered first, keeps the box. The box of 5
+    // dominates the box (2, 2) and evicts 4 from the first front.
+    let solutions = vec![
+        vec![0.0, 5.0],
+        vec![5.0, 0.0],
+        vec![2.2, 2.6],
+        vec![2.6, 2.2],
+        vec![2.1, 2.5],
+        vec![1.5, 1.5],
+    ];
+    let selection = SelectEpsilon {
+        epsilons: vec![1.0, 1.0],
+    };
+
+    let selected = selection.select_and_rank(&solutions, 6, &mo);
+    let mut ranks: Vec<_> = selected.iter().map(|s| (s.index, s.rank)).collect();
+    ranks.sort();
+    assert_eq!(vec![(0, 0), (1, 0), (2, 2), (3, 3), (4, 1), (5, 0)], ranks);
+
+    // Without 5, 4 is in the first front.
+    let selected = selection.select_and_rank(&solutions[..5], 5, &mo);
+    let mut ranks: Vec<_> = selected.iter().map(|s| (s.index, s.rank)).collect();
+    ranks.sort();
+    assert_eq!(vec![(0, 0), (1, 0), (2, 1), (3, 2), (4, 0)], ranks);
+}
+
+#[test]
+fn test_select_epsilon_nan() {
+    use objective::Objective;
+    use multi_objective::MultiObjective;
+    use selection::NanPolicy;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+    let selection = SelectEpsilon {
+        epsilons: vec![1.0, 1.0],
+    };
+
+    // The NaN solution is ranked last.
+    let solutions = vec![vec![0.0, 5.0], vec![5.0, 0.0], vec![2.5, 2.5], vec![f64::NAN, 9.0]];
+    let selected = selection
+        .try_select_and_rank(&solutions, 2, &mo, NanPolicy::Worst)
+        .unwrap();
+    let mut ranks: Vec<_> = selected.iter().map(|s| (s.index, s.rank)).collect();
+    ranks.sort();
+    assert_eq!(2, ranks.len());
+    assert!(ranks.iter().all(|&(index, rank)| index != 3 && rank == 0));
+
+    let selected = selection
+        .try_select_and_rank(&solutions, 4, &mo, NanPolicy::Worst)
+        .unwrap();
+    let nan = selected.iter().find(|s| s.index == 3).unwrap();
+    assert!(selected.iter().all(|s| s.index == 3 || s.rank < nan.rank));
+}
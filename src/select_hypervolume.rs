@@ -0,0 +1,357 @@
+use selection::SelectAndRank;
+use sorting::{sort_fronts, SortAlgorithm};
+use multi_objective::{objective_vector, ObjectiveSet};
+use objective::ToF64;
+use crowding_distance::{cmp_crowding_distance, AssignedCrowdingDistance};
+use indicator::hypervolume::exclusive_hypervolume;
+use non_dominated_sort::Front;
+
+/// Select `n` solutions using the hypervolume based selection of
+/// SMS-EMOA (Beume, Naujoks and Emmerich, 2007).
+///
+/// As in NSGA, we put as many complete pareto fronts into the result
+/// set, as fit. From the last front, that does not completely fit, the
+/// solution with the least exclusive hypervolume contribution is
+/// discarded, one at a time, until we have exactly `n` solutions. The
+/// contributions are recalculated after each removal. For two pareto
+/// objectives, only the contributions of the two neighbours of the
+/// removed solution change, and only these are updated. Unlike the
+/// crowding distance, this maximizes the hypervolume of the selected
+/// front.
+///
+/// The reference point of a front is it's nadir point (the worst value
+/// of each objective within the front) plus `reference_offset` in each
+/// objective, so that the extreme solutions of a front contribute, too.
+///
+/// The `crowding_distance` of a selected solution is it's exclusive
+/// hypervolume contribution within it's front, so that the
+/// crowded-comparison operator prefers solutions with a larger
+/// contribution.
+///
+/// Calculating the contributions is expensive for more than three
+/// objectives. With the engine, use `engine::steady_state_step` for the
+/// steady-state `(mu + 1)` scheme of SMS-EMOA, where only a single
+/// solution is discarded per step.
+#[derive(Clone, Debug)]
+pub struct SelectHypervolume {
+    /// The non-dominated sorting algorithm.
+    pub sort: SortAlgorithm,
+
+    /// Added to the nadir point of a front to obtain the reference
+    /// point. Defaults to 1.
+    pub reference_offset: f64,
+}
+
+impl Default for SelectHypervolume {
+    fn default() -> Self {
+        SelectHypervolume {
+            sort: SortAlgorithm::default(),
+            reference_offset: 1.0,
+        }
+    }
+}
+
+/// The objective vectors of the solutions of `front`, relative to the
+/// reference point of the front, so that all coordinates are positive
+/// and larger coordinates are better.
+fn relative_to_nadir<S, M>(front: &Front<S>, multi_objective: &M, reference_offset: f64) -> Vec<Vec<f64>>
+where
+    M: ObjectiveSet<Solution = S>,
+    M::Distance: ToF64,
+{
+    let origin = front.solutions[0].solution;
+    let mut points: Vec<Vec<f64>> = front
+        .solutions
+        .iter()
+        .map(|s| objective_vector(multi_objective, s.solution, origin))
+        .collect();
+
+    for i in 0..multi_objective.num_objectives() {
+        let nadir = points.iter().map(|p| p[i]).fold(f64::NEG_INFINITY, f64::max);
+        for p in points.iter_mut() {
+            p[i] = nadir + reference_offset - p[i];
+        }
+    }
+    points
+}
+
+/// The exclusive hypervolume contribution of each of `points` (given by
+/// index) with respect to the others.
+fn contributions(points: &[Vec<f64>], among: &[usize]) -> Vec<f64> {
+    among
+        .iter()
+        .map(|&i| {
+            let others: Vec<Vec<f64>> = among
+                .iter()
+                .filter(|&&j| j != i)
+                .map(|&j| points[j].clone())
+                .collect();
+            exclusive_hypervolume(&points[i], &others)
+        })
+        .collect()
+}
+
+/// Discards the least contributing of `points` until `n` are left, and
+/// returns the indices of the remaining points with their contributions.
+fn discard_least_contributing(points: &[Vec<f64>], n: usize) -> (Vec<usize>, Vec<f64>) {
+    let mut selected: Vec<usize> = (0..points.len()).collect();
+    let mut contribution = contributions(points, &selected);
+
+    while selected.len() > n {
+        let worst = (0..selected.len())
+            .min_by(|&a, &b| cmp_crowding_distance(contribution[b], contribution[a]))
+            .unwrap();
+        selected.remove(worst);
+        contribution = contributions(points, &selected);
+    }
+    (selected, contribution)
+}
+
+/// Like `discard_least_contributing`, but for two-dimensional `points`,
+/// none of which dominates another.
+///
+/// Sorted by descending first coordinate, the second coordinate of the
+/// points ascends, and the contribution of a point is the rectangle
+/// between it and its two neighbours. After a removal, only the
+/// contributions of the neighbours of the removed point change.
+fn discard_least_contributing_2d(points: &[Vec<f64>], n: usize) -> (Vec<usize>, Vec<f64>) {
+    let contribution_at = |sorted: &[usize], pos: usize| -> f64 {
+        let (x, y) = (points[sorted[pos]][0], points[sorted[pos]][1]);
+        let next_x = if pos + 1 < sorted.len() { points[sorted[pos + 1]][0] } else { 0.0 };
+        let previous_y = if pos > 0 { points[sorted[pos - 1]][1] } else { 0.0 };
+        (x - next_x) * (y - previous_y)
+    };
+
+    let mut sorted: Vec<usize> = (0..points.len()).collect();
+    sorted.sort_by(|&a, &b| {
+        points[b][0]
+            .partial_cmp(&points[a][0])
+            .unwrap()
+            .then(points[a][1].partial_cmp(&points[b][1]).unwrap())
+    });
+    let mut contribution: Vec<f64> = (0..sorted.len()).map(|pos| contribution_at(&sorted, pos)).collect();
+
+    while sorted.len() > n {
+        // of equal contributions, discard the first solution, as
+        // `discard_least_contributing` does.
+        let worst = (0..sorted.len())
+            .min_by(|&a, &b| cmp_crowding_distance(contribution[b], contribution[a]).then(sorted[a].cmp(&sorted[b])))
+            .unwrap();
+        sorted.remove(worst);
+        contribution.remove(worst);
+        if worst > 0 {
+            contribution[worst - 1] = contribution_at(&sorted, worst - 1);
+        }
+        if worst < sorted.len() {
+            contribution[worst] = contribution_at(&sorted, worst);
+        }
+    }
+
+    let mut selected: Vec<(usize, f64)> = sorted.into_iter().zip(contribution).collect();
+    selected.sort_by_key(|&(i, _)| i);
+    selected.into_iter().unzip()
+}
+
+impl SelectAndRank for SelectHypervolume {
+    fn select_and_rank<'a, S: 'a, M>(
+        &self,
+        solutions: &'a [S],
+        n: usize,
+        multi_objective: &M,
+    ) -> Vec<AssignedCrowdingDistance<'a, S>>
+    where
+        M: ObjectiveSet<Solution = S>,
+        M::Distance: ToF64,
+    {
+        // Cannot select more solutions than we actually have
+        let n = solutions.len().min(n);
+
+        let mut result = Vec::with_capacity(n);
+        let mut missing_solutions = n;
+
+        for front in sort_fronts(self.sort, solutions, multi_objective) {
+            if missing_solutions == 0 {
+                break;
+            }
+
+            let points = relative_to_nadir(&front, multi_objective, self.reference_offset);
+            let (selected, contribution) =
+                if multi_objective.num_objectives() == 2 && multi_objective.is_pareto_dominance() {
+                    discard_least_contributing_2d(&points, missing_solutions)
+                } else {
+                    discard_least_contributing(&points, missing_solutions)
+                };
+
+            missing_solutions -= selected.len();
+            result.extend(selected.into_iter().zip(contribution).map(|(i, crowding_distance)| {
+                AssignedCrowdingDistance {
+                    index: front.solutions[i].index,
+                    solution: front.solutions[i].solution,
+                    rank: front.rank,
+                    crowding_distance,
+                }
+            }));
+        }
+
+        debug_assert_eq!(n, result.len());
+
+        result
+    }
+}
+
+#[test]
+fn test_select_hypervolume() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+
+    // The first front is (0, 4), (1, 2), (2, 1) and (4, 0), with the
+    // reference point (5, 5). (1, 2) and (2, 1) each contribute 2, the
+    // extremes 1. (3, 3) is dominated and alone in the second front.
+    let solutions = vec![Tuple(0, 4), Tuple(1, 2), Tuple(2, 1), Tuple(4, 0), Tuple(3, 3)];
+    let selection = SelectHypervolume::default();
+
+    let selected = selection.select_and_rank(&solutions, 5, &mo);
+    let mut ranked: Vec<_> = selected
+        .iter()
+        .map(|s| (s.index, s.rank, s.crowding_distance))
+        .collect();
+    ranked.sort_by_key(|&(index, _, _)| index);
+    assert_eq!(
+        vec![(0, 0, 1.0), (1, 0, 2.0), (2, 0, 2.0), (3, 0, 1.0), (4, 1, 1.0)],
+        ranked
+    );
+
+    // Of the extremes, (0, 4) is discarded first. Then (4, 0) is the
+    // least contributing solution.
+    let selected = selection.select_and_rank(&solutions, 2, &mo);
+    let mut ranked: Vec<_> = selected.iter().map(|s| (s.index, s.crowding_distance)).collect();
+    ranked.sort_by_key(|&(index, _)| index);
+    assert_eq!(vec![(1, 3.0), (2, 3.0)], ranked);
+
+    // A larger offset favors the extremes.
+    let selection = SelectHypervolume {
+        reference_offset: 10.0,
+        ..SelectHypervolume::default()
+    };
+    let selected = selection.select_and_rank(&solutions, 2, &mo);
+    let mut indices: Vec<_> = selected.iter().map(|s| s.index).collect();
+    indices.sort();
+    assert_eq!(vec![0, 3], indices);
+
+    assert!(selection.select_and_rank(&solutions, 0, &mo).is_empty());
+}
+
+#[test]
+fn test_discard_least_contributing_2d() {
+    use rand::{Rng, SeedableRng, XorShiftRng};
+
+    // random fronts, of which the first coordinate descends and the
+    // second ascends.
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    for len in 1..20 {
+        let mut xs: Vec<f64> = (0..len).map(|_| rng.gen_range(0.1, 10.0)).collect();
+        let mut ys: Vec<f64> = (0..len).map(|_| rng.gen_range(0.1, 10.0)).collect();
+        xs.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        ys.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let mut points: Vec<Vec<f64>> = xs.into_iter().zip(ys).map(|(x, y)| vec![x, y]).collect();
+        rng.shuffle(&mut points);
+
+        for n in 0..len + 1 {
+            let (expected, expected_contribution) = discard_least_contributing(&points, n);
+            let (selected, contribution) = discard_least_contributing_2d(&points, n);
+            assert_eq!(expected, selected);
+            for (a, b) in contribution.iter().zip(expected_contribution) {
+                assert!((a - b).abs() < 1e-9);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_run_sms_emoa() {
+    use std::cell::Cell;
+    use rand::{Rng, SeedableRng, XorShiftRng};
+    use engine::{initial_population, steady_state_step, Driver, EvoConfig};
+    use indicator::hypervolume::hypervolume;
+    use non_dominated_sort::NonDominatedSort;
+    use selection::NanPolicy;
+    use test_helper_objective::SquaresDriver;
+    use vector_objective::VectorMultiObjective;
+
+    // Remembers the fitness of the last offspring.
+    struct Offspring {
+        driver: SquaresDriver,
+        fitness: Cell<(f64, f64)>,
+    }
+
+    impl Driver for Offspring {
+        type Genome = f64;
+        type Fitness = (f64, f64);
+
+        fn random_genome<R: Rng>(&self, rng: &mut R) -> f64 {
+            self.driver.random_genome(rng)
+        }
+
+        fn fitness(&self, x: &f64) -> (f64, f64) {
+            let fitness = self.driver.fitness(x);
+            self.fitness.set(fitness);
+            fitness
+        }
+
+        fn mate<R: Rng>(&self, rng: &mut R, parent1: &f64, parent2: &f64) -> f64 {
+            self.driver.mate(rng, parent1, parent2)
+        }
+    }
+
+    let driver = Offspring {
+        driver: SquaresDriver { noise: 0.1 },
+        fitness: Cell::new((0.0, 0.0)),
+    };
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let evo_config = EvoConfig {
+        mu: 10,
+        lambda: 10,
+        k: 2,
+        ngen: 0,
+        nan_policy: NanPolicy::Worst,
+    };
+    let mo = VectorMultiObjective::minimize(2);
+    let selection = SelectHypervolume::default();
+    let first_front = |population: &[::engine::Individual<f64, (f64, f64)>]| -> Vec<(f64, f64)> {
+        population.iter().filter(|i| i.rank == 0).map(|i| i.fitness).collect()
+    };
+
+    let mut population = initial_population(&mut rng, &driver, &evo_config, &selection, &mo).unwrap();
+    for _ in 0..300 {
+        let previous = first_front(&population);
+        population = steady_state_step(&mut rng, &driver, &evo_config, &selection, &mo, population).unwrap();
+        assert_eq!(10, population.len());
+
+        // The reference point of the selection is the nadir point of the
+        // first front of the parents and the offspring, plus the offset.
+        let mut candidates = previous.clone();
+        candidates.push(driver.fitness.get());
+        let fronts = NonDominatedSort::new(&candidates, &mo).pareto_fronts();
+        let nadir = |i: usize| {
+            fronts[0]
+                .solutions
+                .iter()
+                .map(|s| if i == 0 { s.solution.0 } else { s.solution.1 })
+                .fold(f64::NEG_INFINITY, f64::max)
+        };
+        let offset = selection.reference_offset;
+        let reference = (nadir(0) + offset, nadir(1) + offset);
+
+        // Against it, the hypervolume of the first front never decreases.
+        let before = hypervolume(&mo, &previous, &reference);
+        let after = hypervolume(&mo, &first_front(&population), &reference);
+        assert!(after >= before - 1e-9);
+    }
+
+    for individual in population.iter() {
+        assert_eq!(0, individual.rank);
+        assert!(individual.genome >= 0.0 && individual.genome <= 2.0);
+    }
+}
@@ -1,8 +1,8 @@
 use selection::SelectAndRank;
-use std::cmp::PartialOrd;
-use non_dominated_sort::NonDominatedSort;
-use multi_objective::MultiObjective;
-use crowding_distance::{assign_crowding_distance, AssignedCrowdingDistance};
+use sorting::{sort_fronts, SortAlgorithm};
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use crowding_distance::{assign_crowding_distance, cmp_crowding_distance, AssignedCrowdingDistance};
 
 pub type RankedSolution<'a, S> = AssignedCrowdingDistance<'a, S>;
 
@@ -17,16 +17,25 @@ pub type RankedSolution<'a, S> = AssignedCrowdingDistance<'a, S>;
 /// distance (higher crowding distance is "better"), and prefer those
 /// solutions with the higher crowding distance until we have exactly
 /// `n` solutions in the result set.
-
-pub struct SelectNSGA;
+#[derive(Clone, Debug, Default)]
+pub struct SelectNSGA {
+    /// The non-dominated sorting algorithm. For large populations, one
+    /// of the algorithms that work on the objective values directly is
+    /// much faster than the default.
+    pub sort: SortAlgorithm,
+}
 
 impl SelectAndRank for SelectNSGA {
-    fn select_and_rank<'a, S: 'a>(
+    fn select_and_rank<'a, S: 'a, M>(
         &self,
         solutions: &'a [S],
         n: usize,
-        multi_objective: &MultiObjective<S, f64>,
-    ) -> Vec<RankedSolution<'a, S>> {
+        multi_objective: &M,
+    ) -> Vec<RankedSolution<'a, S>>
+    where
+        M: ObjectiveSet<Solution = S>,
+        M::Distance: ToF64,
+    {
         // Cannot select more solutions than we actually have
         let n = solutions.len().min(n);
         debug_assert!(n <= solutions.len());
@@ -34,7 +43,7 @@ impl SelectAndRank for SelectNSGA {
         let mut result = Vec::with_capacity(n);
         let mut missing_solutions = n;
 
-        for front in NonDominatedSort::new(solutions, multi_objective) {
+        for front in sort_fronts(self.sort, solutions, multi_objective) {
             let (mut assigned_crowding, _) = assign_crowding_distance(&front, multi_objective);
 
             if assigned_crowding.len() > missing_solutions {
@@ -44,10 +53,7 @@ impl SelectAndRank for SelectNSGA {
 
                 assigned_crowding.sort_by(|a, b| {
                     debug_assert_eq!(a.rank, b.rank);
-                    a.crowding_distance
-                        .partial_cmp(&b.crowding_distance)
-                        .unwrap()
-                        .reverse()
+                    cmp_crowding_distance(a.crowding_distance, b.crowding_distance)
                 });
             }
 
@@ -64,6 +70,6 @@ impl SelectAndRank for SelectNSGA {
 
         debug_assert_eq!(n, result.len());
 
-        return result;
+        result
     }
 }
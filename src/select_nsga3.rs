@@ -0,0 +1,371 @@
+use selection::SelectAndRank;
+use sorting::{sort_fronts, SortAlgorithm};
+use multi_objective::{objective_vector, ObjectiveSet};
+use crowding_distance::AssignedCrowdingDistance;
+use objective::{nan_worst_cmp, ToF64};
+use select_nsga::RankedSolution;
+
+/// Select `n` solutions using the reference-point based approach of
+/// NSGA-III (Deb and Jain, 2014).
+///
+/// As in NSGA, we put as many complete pareto fronts into the result
+/// set, as fit. The remaining solutions are chosen from the last front
+/// such that they are well spread over a set of reference directions,
+/// instead of relying on the crowding distance, which performs poorly
+/// for more than three objectives.
+///
+/// The reference directions are the Das-Dennis points with `divisions`
+/// divisions per objective. The objectives of the candidate solutions
+/// are normalized using the ideal point and the intercepts of the
+/// hyperplane spanned by the extreme points. Each candidate is
+/// associated with the reference direction with the smallest
+/// perpendicular distance. Then, solutions of the last front are added
+/// one by one to the least crowded reference direction (niche).
+///
+/// Unlike the original algorithm, ties are broken deterministically:
+/// among the least crowded niches, the first one is chosen, and within a
+/// niche, the solution closest to the reference direction is chosen.
+///
+/// NSGA-III does not use the crowding distance. Instead, the
+/// `crowding_distance` of the selected solutions is set to the negated
+/// perpendicular distance to their reference direction, so that within
+/// a front, the crowded-comparison operator prefers solutions close to
+/// a reference direction.
+///
+/// The objectives need to be numeric, that is, the distance between two
+/// solutions has to be the difference of their objective values.
+#[derive(Clone, Debug)]
+pub struct SelectNSGA3 {
+    /// The number of divisions per objective used to generate the
+    /// reference directions.
+    pub divisions: usize,
+
+    /// The non-dominated sorting algorithm.
+    pub sort: SortAlgorithm,
+}
+
+impl SelectNSGA3 {
+    /// Uses the default sorting algorithm.
+    pub fn new(divisions: usize) -> Self {
+        SelectNSGA3 {
+            divisions,
+            sort: SortAlgorithm::default(),
+        }
+    }
+}
+
+/// Generates the structured reference points of Das and Dennis on the
+/// unit simplex: all points whose coordinates are multiples of
+/// `1 / divisions` and sum up to one.
+///
+/// There are `(num_objectives + divisions - 1) choose divisions` such
+/// points.
+pub fn das_dennis_reference_points(num_objectives: usize, divisions: usize) -> Vec<Vec<f64>> {
+    fn generate(
+        remaining_objectives: usize,
+        remaining: usize,
+        divisions: usize,
+        current: &mut Vec<f64>,
+        result: &mut Vec<Vec<f64>>,
+    ) {
+        if remaining_objectives == 1 {
+            current.push(remaining as f64 / divisions as f64);
+            result.push(current.clone());
+            current.pop();
+        } else {
+            for k in 0..remaining + 1 {
+                current.push(k as f64 / divisions as f64);
+                generate(remaining_objectives - 1, remaining - k, divisions, current, result);
+                current.pop();
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    if num_objectives == 0 || divisions == 0 {
+        return result;
+    }
+    generate(num_objectives, divisions, divisions, &mut Vec::new(), &mut result);
+    result
+}
+
+/// Solves the linear system `a * x = b` using gaussian elimination with
+/// partial pivoting. Returns `None` if `a` is (nearly) singular.
+fn solve_linear_system(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+
+    for col in 0..n {
+        // the row with the largest absolute value, NaN counts as -1
+        let magnitude = |row: &[f64]| {
+            let x = row[col].abs();
+            if x.is_nan() {
+                -1.0
+            } else {
+                x
+            }
+        };
+        let pivot = (col..n).max_by(|&i, &j| magnitude(&a[i]).partial_cmp(&magnitude(&a[j])).unwrap())?;
+        if magnitude(&a[pivot]) < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        for row in col + 1..n {
+            let factor = a[row][col] / a[col][col];
+            let (upper, lower) = a.split_at_mut(row);
+            for (x, p) in lower[0][col..].iter_mut().zip(upper[col][col..].iter()) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// Normalizes `points` in place: translates the ideal point into the
+/// origin and scales each objective by the intercept of the hyperplane
+/// through the extreme points.
+fn normalize(points: &mut [Vec<f64>], num_objectives: usize) {
+    // translate by ideal point
+    for i in 0..num_objectives {
+        let ideal = points.iter().map(|p| p[i]).fold(f64::INFINITY, f64::min);
+        for p in points.iter_mut() {
+            p[i] -= ideal;
+        }
+    }
+
+    // the extreme point of each axis minimizes the achievement
+    // scalarizing function with a weight vector close to that axis.
+    let extreme_points: Vec<Vec<f64>> = (0..num_objectives)
+        .map(|axis| {
+            let asf = |p: &Vec<f64>| {
+                (0..num_objectives)
+                    .map(|i| p[i] / if i == axis { 1.0 } else { 1e-6 })
+                    .fold(f64::NEG_INFINITY, f64::max)
+            };
+            points
+                .iter()
+                .min_by(|a, b| nan_worst_cmp(asf(a), asf(b)))
+                .unwrap()
+                .clone()
+        })
+        .collect();
+
+    let intercepts = solve_linear_system(extreme_points, vec![1.0; num_objectives])
+        .map(|x| x.into_iter().map(|xi| 1.0 / xi).collect::<Vec<_>>())
+        .filter(|intercepts| intercepts.iter().all(|&a| a.is_finite() && a > 1e-10));
+
+    // Fall back to the worst value of each objective, if the
+    // hyperplane is degenerate.
+    let intercepts = intercepts.unwrap_or_else(|| {
+        (0..num_objectives)
+            .map(|i| {
+                let worst = points.iter().map(|p| p[i]).fold(0.0, f64::max);
+                if worst > 1e-10 {
+                    worst
+                } else {
+                    1.0
+                }
+            })
+            .collect()
+    });
+
+    for p in points.iter_mut() {
+        for i in 0..num_objectives {
+            p[i] /= intercepts[i];
+        }
+    }
+}
+
+/// Returns the index of the nearest reference direction and the
+/// perpendicular distance of `point` to it.
+fn associate(point: &[f64], reference_points: &[Vec<f64>]) -> (usize, f64) {
+    reference_points
+        .iter()
+        .map(|w| {
+            let w_norm2: f64 = w.iter().map(|x| x * x).sum();
+            let dot: f64 = w.iter().zip(point.iter()).map(|(a, b)| a * b).sum();
+            let t = dot / w_norm2;
+            w.iter()
+                .zip(point.iter())
+                .map(|(wi, pi)| (pi - t * wi) * (pi - t * wi))
+                .sum::<f64>()
+                .sqrt()
+        })
+        .enumerate()
+        .min_by(|a, b| nan_worst_cmp(a.1, b.1))
+        .unwrap()
+}
+
+impl SelectAndRank for SelectNSGA3 {
+    fn select_and_rank<'a, S: 'a, M>(
+        &self,
+        solutions: &'a [S],
+        n: usize,
+        multi_objective: &M,
+    ) -> Vec<RankedSolution<'a, S>>
+    where
+        M: ObjectiveSet<Solution = S>,
+        M::Distance: ToF64,
+    {
+        // Cannot select more solutions than we actually have
+        let n = solutions.len().min(n);
+        if n == 0 {
+            return Vec::new();
+        }
+
+        // Collect the fronts until we have at least `n` candidates.
+        let mut fronts = Vec::new();
+        let mut num_candidates = 0;
+        for front in sort_fronts(self.sort, solutions, multi_objective) {
+            num_candidates += front.solutions.len();
+            fronts.push(front);
+            if num_candidates >= n {
+                break;
+            }
+        }
+
+        let candidates: Vec<_> = fronts
+            .iter()
+            .flat_map(|front| front.solutions.iter().map(move |s| (s.index, s.solution, front.rank)))
+            .collect();
+        let last_rank = fronts.last().unwrap().rank;
+
+        // Objective vectors of all candidates, relative to an arbitrary
+        // candidate.
+        let num_objectives = multi_objective.num_objectives();
+        let origin = candidates[0].1;
+        let mut points: Vec<Vec<f64>> = candidates
+            .iter()
+            .map(|&(_, solution, _)| objective_vector(multi_objective, solution, origin))
+            .collect();
+        normalize(&mut points, num_objectives);
+
+        let reference_points = das_dennis_reference_points(num_objectives, self.divisions.max(1));
+        let associations: Vec<(usize, f64)> = points
+            .iter()
+            .map(|p| associate(p, &reference_points))
+            .collect();
+
+        let mut result = Vec::with_capacity(n);
+        let mut niche_count = vec![0; reference_points.len()];
+        let mut last_front = Vec::new();
+
+        for (i, &(index, solution, rank)) in candidates.iter().enumerate() {
+            let (reference, distance) = associations[i];
+            if rank < last_rank || num_candidates == n {
+                niche_count[reference] += 1;
+                result.push(AssignedCrowdingDistance {
+                    index,
+                    solution,
+                    rank,
+                    crowding_distance: -distance,
+                });
+            } else {
+                last_front.push(i);
+            }
+        }
+
+        // Niching: fill up the result from the last front.
+        let mut excluded = vec![false; reference_points.len()];
+        while result.len() < n {
+            let reference = (0..reference_points.len())
+                .filter(|&j| !excluded[j])
+                .min_by_key(|&j| niche_count[j])
+                .unwrap();
+
+            let closest = last_front
+                .iter()
+                .enumerate()
+                .filter(|&(_, &i)| associations[i].0 == reference)
+                .min_by(|a, b| nan_worst_cmp(associations[*a.1].1, associations[*b.1].1))
+                .map(|(pos, _)| pos);
+
+            match closest {
+                None => {
+                    excluded[reference] = true;
+                }
+                Some(pos) => {
+                    let i = last_front.swap_remove(pos);
+                    let (index, solution, rank) = candidates[i];
+                    niche_count[reference] += 1;
+                    result.push(AssignedCrowdingDistance {
+                        index,
+                        solution,
+                        rank,
+                        crowding_distance: -associations[i].1,
+                    });
+                }
+            }
+        }
+
+        debug_assert_eq!(n, result.len());
+
+        result
+    }
+}
+
+#[test]
+fn test_das_dennis_reference_points() {
+    let points = das_dennis_reference_points(3, 4);
+    // (3 + 4 - 1) choose 4
+    assert_eq!(15, points.len());
+    for p in points.iter() {
+        assert_eq!(3, p.len());
+        assert!((p.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    }
+
+    assert_eq!(vec![vec![0.0, 1.0], vec![0.5, 0.5], vec![1.0, 0.0]], das_dennis_reference_points(2, 2));
+}
+
+#[test]
+fn test_solve_linear_system() {
+    // needs a row swap, as the first pivot is 0
+    let a = vec![vec![0.0, 1.0], vec![2.0, 0.0]];
+    assert_eq!(Some(vec![1.5, 2.0]), solve_linear_system(a, vec![2.0, 3.0]));
+
+    assert_eq!(None, solve_linear_system(vec![vec![1.0, 2.0], vec![2.0, 4.0]], vec![1.0, 1.0]));
+    // NaN is never a pivot
+    assert_eq!(None, solve_linear_system(vec![vec![f64::NAN]], vec![1.0]));
+}
+
+#[test]
+fn test_select_nsga3() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+
+    // The first front lies on the line x + y = 4. Tuple(3, 3) is
+    // dominated.
+    let solutions = vec![Tuple(0, 4), Tuple(1, 3), Tuple(2, 2), Tuple(3, 3), Tuple(3, 1), Tuple(4, 0)];
+
+    // With reference directions (0, 1), (0.5, 0.5) and (1, 0), the
+    // extremes and the center are chosen.
+    let selected = SelectNSGA3::new(2).select_and_rank(&solutions, 3, &mo);
+    let mut indices: Vec<_> = selected.iter().map(|s| s.index).collect();
+    indices.sort();
+    assert_eq!(vec![0, 2, 5], indices);
+    assert!(selected.iter().all(|s| s.rank == 0));
+
+    // The complete first front plus the dominated solution.
+    let selected = SelectNSGA3::new(2).select_and_rank(&solutions, 6, &mo);
+    assert_eq!(6, selected.len());
+    assert_eq!(1, selected.iter().find(|s| s.index == 3).unwrap().rank);
+
+    // The faster sorting algorithms select the same solutions.
+    let selection = SelectNSGA3 {
+        divisions: 2,
+        sort: SortAlgorithm::EnsBinary,
+    };
+    let mut indices: Vec<_> = selection.select_and_rank(&solutions, 3, &mo).iter().map(|s| s.index).collect();
+    indices.sort();
+    assert_eq!(vec![0, 2, 5], indices);
+}
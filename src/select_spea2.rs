@@ -0,0 +1,252 @@
+use std::cmp::Ordering;
+use selection::SelectAndRank;
+use multi_objective::{objective_vector, ObjectiveSet};
+use crowding_distance::AssignedCrowdingDistance;
+use objective::{nan_worst_cmp, ToF64};
+
+/// Select `n` solutions using the environmental selection of SPEA2
+/// (Zitzler, Laumanns and Thiele, 2001).
+///
+/// Each solution is assigned a *strength*, the number of solutions it
+/// dominates, and a *raw fitness*, the sum of the strengths of the
+/// solutions dominating it. The non-dominated solutions have a raw
+/// fitness of 0. The *density* of a solution decreases with the distance
+/// to it's `k`-th nearest neighbor in objective space.
+///
+/// All non-dominated solutions are selected. If these are fewer than
+/// `n`, the dominated solutions with the lowest raw fitness are added,
+/// preferring the less dense ones. If they are more than `n`, the
+/// solution with the smallest distance to it's nearest neighbor is
+/// removed, one at a time, until `n` solutions remain. Ties are broken
+/// by the distance to the second nearest neighbor, and so on.
+///
+/// The `rank` of a selected solution is the position of it's raw fitness
+/// among the distinct raw fitness values of the selected solutions, so
+/// that the non-dominated solutions have rank 0 and no rank is empty.
+/// The `crowding_distance` is the distance to it's `k`-th nearest
+/// neighbor. As the density is less than 1, the crowded-comparison
+/// operator then orders solutions by the SPEA2 fitness (raw fitness
+/// plus density).
+///
+/// SPEA2 keeps a separate archive. Used with the engine, the population
+/// takes the role of the archive.
+#[derive(Clone, Debug, Default)]
+pub struct SelectSPEA2 {
+    /// The neighbor used for the density estimation. Defaults to the
+    /// square root of the number of solutions.
+    pub k: Option<usize>,
+}
+
+/// The euclidean distance between all pairs of solutions in objective
+/// space.
+fn distance_matrix<S, M>(solutions: &[S], multi_objective: &M) -> Vec<Vec<f64>>
+where
+    M: ObjectiveSet<Solution = S>,
+    M::Distance: ToF64,
+{
+    solutions
+        .iter()
+        .map(|a| {
+            solutions
+                .iter()
+                .map(|b| {
+                    objective_vector(multi_objective, a, b)
+                        .iter()
+                        .map(|d| d * d)
+                        .sum::<f64>()
+                        .sqrt()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// The distances of `i` to the other solutions in `among`, ascending.
+fn sorted_distances(distances: &[Vec<f64>], i: usize, among: &[usize]) -> Vec<f64> {
+    let mut d: Vec<f64> = among
+        .iter()
+        .filter(|&&j| j != i)
+        .map(|&j| distances[i][j])
+        .collect();
+    d.sort_by(|&a, &b| nan_worst_cmp(a, b));
+    d
+}
+
+fn lexicographic_cmp(a: &[f64], b: &[f64]) -> Ordering {
+    a.iter()
+        .zip(b.iter())
+        .map(|(&x, &y)| nan_worst_cmp(x, y))
+        .find(|&o| o != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+impl SelectAndRank for SelectSPEA2 {
+    fn select_and_rank<'a, S: 'a, M>(
+        &self,
+        solutions: &'a [S],
+        n: usize,
+        multi_objective: &M,
+    ) -> Vec<AssignedCrowdingDistance<'a, S>>
+    where
+        M: ObjectiveSet<Solution = S>,
+        M::Distance: ToF64,
+    {
+        let len = solutions.len();
+        // Cannot select more solutions than we actually have
+        let n = len.min(n);
+        if n == 0 {
+            return Vec::new();
+        }
+
+        // dominated_by[i] are the solutions dominating `i`.
+        let mut strength = vec![0; len];
+        let mut dominated_by = vec![Vec::new(); len];
+        for i in 0..len {
+            for j in i + 1..len {
+                match multi_objective.domination_ord(&solutions[i], &solutions[j]) {
+                    Ordering::Less => {
+                        strength[i] += 1;
+                        dominated_by[j].push(i);
+                    }
+                    Ordering::Greater => {
+                        strength[j] += 1;
+                        dominated_by[i].push(j);
+                    }
+                    Ordering::Equal => {}
+                }
+            }
+        }
+        let raw_fitness: Vec<usize> = dominated_by
+            .iter()
+            .map(|dominators| dominators.iter().map(|&j| strength[j]).sum())
+            .collect();
+
+        let distances = distance_matrix(solutions, multi_objective);
+        let all: Vec<usize> = (0..len).collect();
+        let k = self.k.unwrap_or_else(|| (len as f64).sqrt() as usize).max(1);
+        let kth_distance: Vec<f64> = (0..len)
+            .map(|i| {
+                sorted_distances(&distances, i, &all)
+                    .get(k - 1)
+                    .cloned()
+                    .unwrap_or(f64::INFINITY)
+            })
+            .collect();
+
+        let mut selected: Vec<usize> = (0..len).filter(|&i| raw_fitness[i] == 0).collect();
+
+        if selected.len() < n {
+            // fill up with the best dominated solutions
+            let mut dominated: Vec<usize> = (0..len).filter(|&i| raw_fitness[i] > 0).collect();
+            dominated.sort_by(|&a, &b| {
+                raw_fitness[a]
+                    .cmp(&raw_fitness[b])
+                    .then_with(|| nan_worst_cmp(kth_distance[b], kth_distance[a]))
+            });
+            let missing = n - selected.len();
+            selected.extend(dominated.into_iter().take(missing));
+        } else {
+            // truncate, removing the most crowded solution one at a time
+            while selected.len() > n {
+                let nearest: Vec<Vec<f64>> = selected
+                    .iter()
+                    .map(|&i| sorted_distances(&distances, i, &selected))
+                    .collect();
+                let remove = (0..selected.len())
+                    .min_by(|&a, &b| lexicographic_cmp(&nearest[a], &nearest[b]))
+                    .unwrap();
+                selected.remove(remove);
+            }
+        }
+
+        debug_assert_eq!(n, selected.len());
+
+        // the raw fitness values are sums of strengths, with gaps in
+        // between.
+        let mut distinct: Vec<usize> = selected.iter().map(|&i| raw_fitness[i]).collect();
+        distinct.sort();
+        distinct.dedup();
+
+        selected
+            .into_iter()
+            .map(|index| AssignedCrowdingDistance {
+                index,
+                solution: &solutions[index],
+                rank: distinct.binary_search(&raw_fitness[index]).unwrap(),
+                crowding_distance: kth_distance[index],
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_select_spea2() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+
+    // (1, 3), (3, 1) and (2, 2) are non-dominated, each dominating
+    // (3, 3) and (4, 4). (3, 3) dominates (4, 4).
+    let solutions = vec![Tuple(1, 3), Tuple(3, 1), Tuple(2, 2), Tuple(3, 3), Tuple(4, 4)];
+
+    // Raw fitness 2 + 2 + 2 for (3, 3), and 2 + 2 + 2 + 1 for (4, 4),
+    // which are ranked by their order.
+    let selected = SelectSPEA2::default().select_and_rank(&solutions, 4, &mo);
+    let mut ranks: Vec<_> = selected.iter().map(|s| (s.index, s.rank)).collect();
+    ranks.sort();
+    assert_eq!(vec![(0, 0), (1, 0), (2, 0), (3, 1)], ranks);
+
+    // k = 2: the second nearest neighbor of (1, 3) is (3, 3).
+    assert_eq!(2.0, selected.iter().find(|s| s.index == 0).unwrap().crowding_distance);
+
+    let selected = SelectSPEA2::default().select_and_rank(&solutions, 5, &mo);
+    assert_eq!(2, selected.iter().find(|s| s.index == 4).unwrap().rank);
+
+    // Truncation: (2, 2) is closest to both others.
+    let selected = SelectSPEA2::default().select_and_rank(&solutions, 2, &mo);
+    let mut indices: Vec<_> = selected.iter().map(|s| s.index).collect();
+    indices.sort();
+    assert_eq!(vec![0, 1], indices);
+
+    // Of the dominated solutions, the one with the lower raw fitness.
+    let solutions = vec![Tuple(0, 0), Tuple(5, 5), Tuple(1, 1)];
+    let selected = SelectSPEA2 { k: Some(1) }.select_and_rank(&solutions, 2, &mo);
+    let indices: Vec<_> = selected.iter().map(|s| (s.index, s.rank)).collect();
+    assert_eq!(vec![(0, 0), (2, 1)], indices);
+
+    // In a chain, the raw fitness is 0, 5, 5 + 4, ..., but the ranks have
+    // no gaps.
+    let solutions: Vec<_> = (0..6).map(|i| Tuple(i, i)).collect();
+    let selected = SelectSPEA2::default().select_and_rank(&solutions, 6, &mo);
+    let mut ranks: Vec<_> = selected.iter().map(|s| (s.index, s.rank)).collect();
+    ranks.sort();
+    assert_eq!((0..6).map(|i| (i, i)).collect::<Vec<_>>(), ranks);
+}
+
+#[test]
+fn test_run_spea2() {
+    use rand::{SeedableRng, XorShiftRng};
+    use engine::{run, EvoConfig};
+    use selection::NanPolicy;
+    use test_helper_objective::SquaresDriver;
+    use vector_objective::VectorMultiObjective;
+
+    let driver = SquaresDriver::default();
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let evo_config = EvoConfig {
+        mu: 20,
+        lambda: 20,
+        k: 2,
+        ngen: 30,
+        nan_policy: NanPolicy::Worst,
+    };
+    let mo = VectorMultiObjective::minimize(2);
+
+    let population = run(&mut rng, &driver, &evo_config, &SelectSPEA2::default(), &mo).unwrap();
+    assert_eq!(20, population.len());
+    for individual in population.iter() {
+        assert_eq!(0, individual.rank);
+        assert!(individual.genome >= 0.0 && individual.genome <= 2.0);
+    }
+}
@@ -1,11 +1,155 @@
-use multi_objective::MultiObjective;
+use std::error::Error;
+use std::fmt;
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
 use crowding_distance::AssignedCrowdingDistance;
+use nan_worst::{invalid_objective, NanWorst};
+
+/// How to treat solutions with invalid (NaN or infinite) objective
+/// values, see `nan_worst::is_valid`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum NanPolicy {
+    /// Invalid solutions are worse than any valid solution. They are
+    /// ranked after all valid solutions.
+    #[default]
+    Worst,
+
+    /// Selection fails with `SelectionError::InvalidValue`.
+    Reject,
+}
+
+/// Why a selection failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SelectionError {
+    /// There are no solutions to select from.
+    NoSolutions,
+
+    /// A solution has an invalid value in an objective.
+    InvalidValue { solution: usize, objective: usize },
+}
+
+impl fmt::Display for SelectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SelectionError::NoSolutions => write!(f, "no solutions to select from"),
+            SelectionError::InvalidValue { solution, objective } => write!(
+                f,
+                "solution {} has an invalid value in objective {}",
+                solution, objective
+            ),
+        }
+    }
+}
+
+impl Error for SelectionError {}
+
+/// Fails with `SelectionError::InvalidValue` if `nan_policy` rejects
+/// `solution`, the `index`-th solution, as `try_select_and_rank` does.
+/// For algorithms that do not select through `try_select_and_rank`.
+pub(crate) fn check_nan_policy<M>(
+    index: usize,
+    solution: &M::Solution,
+    multi_objective: &M,
+    nan_policy: NanPolicy,
+) -> Result<(), SelectionError>
+where
+    M: ObjectiveSet,
+    M::Distance: ToF64,
+{
+    match (nan_policy, invalid_objective(multi_objective, solution)) {
+        (NanPolicy::Reject, Some(objective)) => Err(SelectionError::InvalidValue {
+            solution: index,
+            objective,
+        }),
+        _ => Ok(()),
+    }
+}
 
 pub trait SelectAndRank {
-    fn select_and_rank<'a, S: 'a>(
+    fn select_and_rank<'a, S: 'a, M>(
+        &self,
+        solutions: &'a [S],
+        n: usize,
+        multi_objective: &M,
+    ) -> Vec<AssignedCrowdingDistance<'a, S>>
+    where
+        M: ObjectiveSet<Solution = S>,
+        M::Distance: ToF64;
+
+    /// Like `select_and_rank`, but fails if there are no solutions, and
+    /// treats solutions with invalid objective values according to
+    /// `nan_policy` instead of passing them to the objectives.
+    fn try_select_and_rank<'a, S: 'a, M>(
         &self,
         solutions: &'a [S],
         n: usize,
-        multi_objective: &MultiObjective<S, f64>,
-    ) -> Vec<AssignedCrowdingDistance<'a, S>>;
+        multi_objective: &M,
+        nan_policy: NanPolicy,
+    ) -> Result<Vec<AssignedCrowdingDistance<'a, S>>, SelectionError>
+    where
+        M: ObjectiveSet<Solution = S>,
+        M::Distance: ToF64,
+    {
+        if solutions.is_empty() {
+            return Err(SelectionError::NoSolutions);
+        }
+
+        let invalid = solutions
+            .iter()
+            .enumerate()
+            .filter_map(|(solution, s)| {
+                invalid_objective(multi_objective, s).map(|objective| SelectionError::InvalidValue {
+                    solution,
+                    objective,
+                })
+            })
+            .next();
+
+        match (invalid, nan_policy) {
+            (None, _) => Ok(self.select_and_rank(solutions, n, multi_objective)),
+            (Some(_), NanPolicy::Worst) => Ok(self.select_and_rank(solutions, n, &NanWorst::new(multi_objective))),
+            (Some(error), NanPolicy::Reject) => Err(error),
+        }
+    }
+}
+
+#[test]
+fn test_try_select_and_rank() {
+    use objective::Objective;
+    use multi_objective::MultiObjective;
+    use select_nsga::SelectNSGA;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+    let selection = SelectNSGA::default();
+
+    let solutions: Vec<Point> = vec![];
+    assert_eq!(
+        Err(SelectionError::NoSolutions),
+        selection.try_select_and_rank(&solutions, 1, &mo, NanPolicy::Worst).map(|s| s.len())
+    );
+
+    let solutions = vec![vec![f64::NAN, 0.0], vec![2.0, 2.0], vec![1.0, 3.0], vec![3.0, 1.0], vec![0.0, f64::NAN]];
+    assert_eq!(
+        Err(SelectionError::InvalidValue {
+            solution: 0,
+            objective: 0,
+        }),
+        selection.try_select_and_rank(&solutions, 3, &mo, NanPolicy::Reject).map(|s| s.len())
+    );
+
+    // The invalid solutions form the last front.
+    let mut selected: Vec<_> = selection
+        .try_select_and_rank(&solutions, 5, &mo, NanPolicy::Worst)
+        .unwrap()
+        .iter()
+        .map(|s| (s.index, s.rank))
+        .collect();
+    selected.sort();
+    assert_eq!(vec![(0, 1), (1, 0), (2, 0), (3, 0), (4, 1)], selected);
+
+    let selected = selection.try_select_and_rank(&solutions, 3, &mo, NanPolicy::Worst).unwrap();
+    assert!(selected.iter().all(|s| s.rank == 0));
 }
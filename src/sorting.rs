@@ -0,0 +1,465 @@
+//! Non-dominated sorting algorithms.
+//!
+//! The fast non-dominated sort of the non-dominated-sort crate compares
+//! every pair of solutions and works with any `DominationOrd`. The other
+//! algorithms work on the objective values directly and are much faster
+//! for large populations. They require the domination to be the plain
+//! pareto dominance of the objectives (see
+//! `ObjectiveSet::is_pareto_dominance`) and otherwise fall back to the
+//! fast non-dominated sort.
+//!
+//! As only the order of the objective values matters for pareto
+//! dominance, each objective value is replaced by it's rank among all
+//! solutions according to the total order of the objective.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use non_dominated_sort::{Front, NonDominatedSort, SolutionWithIndex};
+use multi_objective::ObjectiveSet;
+
+/// The algorithm used to sort solutions into pareto fronts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortAlgorithm {
+    /// The fast non-dominated sort of Deb et al. in `O(M N^2)`.
+    #[default]
+    Fast,
+
+    /// The divide-and-conquer sort of Jensen in `O(N log^(M-1) N)`,
+    /// generalized by Fortin et al. and Buzdalov and Shalyto to handle
+    /// equal objective values.
+    DivideAndConquer,
+
+    /// The efficient non-dominated sort (ENS) of Zhang et al. with a
+    /// sequential search for the front of a solution.
+    EnsSequential,
+
+    /// The efficient non-dominated sort (ENS) of Zhang et al. with a
+    /// binary search for the front of a solution.
+    EnsBinary,
+}
+
+/// Sorts `solutions` into pareto fronts using `algorithm`. Returns all
+/// fronts, the best front first.
+pub fn sort_fronts<'a, S, M>(
+    algorithm: SortAlgorithm,
+    solutions: &'a [S],
+    multi_objective: &M,
+) -> Vec<Front<'a, S>>
+where
+    M: ObjectiveSet<Solution = S>,
+{
+    if algorithm == SortAlgorithm::Fast || !multi_objective.is_pareto_dominance() {
+        return NonDominatedSort::new(solutions, multi_objective).collect();
+    }
+
+    let points = objective_ranks(solutions, multi_objective);
+    let ranks = match algorithm {
+        SortAlgorithm::DivideAndConquer => divide_and_conquer_sort(&points),
+        SortAlgorithm::EnsBinary => efficient_non_dominated_sort(&points, true),
+        _ => efficient_non_dominated_sort(&points, false),
+    };
+
+    let num_fronts = ranks.iter().max().map_or(0, |r| r + 1);
+    let mut fronts: Vec<_> = (0..num_fronts)
+        .map(|rank| Front {
+            rank,
+            solutions: Vec::new(),
+        })
+        .collect();
+    for (index, (solution, &rank)) in solutions.iter().zip(ranks.iter()).enumerate() {
+        fronts[rank]
+            .solutions
+            .push(SolutionWithIndex { solution, index });
+    }
+    fronts
+}
+
+/// Replaces each objective value by it's rank among all solutions.
+/// Equal values get the same rank.
+fn objective_ranks<S, M>(solutions: &[S], multi_objective: &M) -> Vec<Vec<usize>>
+where
+    M: ObjectiveSet<Solution = S>,
+{
+    let num_objectives = multi_objective.num_objectives();
+    let mut points = vec![Vec::with_capacity(num_objectives); solutions.len()];
+    let mut order: Vec<usize> = (0..solutions.len()).collect();
+    let mut ranks = vec![0; solutions.len()];
+
+    for i in 0..num_objectives {
+        let objective = multi_objective.objective(i);
+        order.sort_by(|&a, &b| objective.total_order(&solutions[a], &solutions[b]));
+
+        let mut rank = 0;
+        if let Some(&first) = order.first() {
+            ranks[first] = rank;
+        }
+        for w in order.windows(2) {
+            if objective.total_order(&solutions[w[0]], &solutions[w[1]]) == Ordering::Less {
+                rank += 1;
+            }
+            ranks[w[1]] = rank;
+        }
+
+        for (point, &rank) in points.iter_mut().zip(ranks.iter()) {
+            point.push(rank);
+        }
+    }
+    points
+}
+
+fn dominates(a: &[usize], b: &[usize]) -> bool {
+    a.iter().zip(b.iter()).all(|(x, y)| x <= y) && a != b
+}
+
+/// Returns the front of each point.
+///
+/// The points are processed in lexicographic order, so that a point can
+/// only be dominated by points processed before it. Each point is put
+/// into the first front none of whose members dominates it.
+fn efficient_non_dominated_sort(points: &[Vec<usize>], binary_search: bool) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| points[a].cmp(&points[b]));
+
+    let mut ranks = vec![0; points.len()];
+    let mut fronts: Vec<Vec<usize>> = Vec::new();
+
+    for &s in order.iter() {
+        // The most recently added members are most likely to dominate
+        // `s`.
+        let dominated_by = |front: &Vec<usize>| {
+            front
+                .iter()
+                .rev()
+                .any(|&t| dominates(&points[t], &points[s]))
+        };
+
+        let rank = if binary_search {
+            // If `s` is dominated by a member of a front, it is also
+            // dominated by a member of each previous front.
+            let (mut lo, mut hi) = (0, fronts.len());
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                if dominated_by(&fronts[mid]) {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            lo
+        } else {
+            fronts
+                .iter()
+                .position(|front| !dominated_by(front))
+                .unwrap_or(fronts.len())
+        };
+
+        if rank == fronts.len() {
+            fronts.push(Vec::new());
+        }
+        fronts[rank].push(s);
+        ranks[s] = rank;
+    }
+    ranks
+}
+
+/// Returns the front of each point.
+fn divide_and_conquer_sort(points: &[Vec<usize>]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| points[a].cmp(&points[b]));
+
+    // Equal points share their front. Only the distinct points are
+    // sorted, in lexicographic order.
+    let mut distinct: Vec<&[usize]> = Vec::new();
+    let mut distinct_index = vec![0; points.len()];
+    for &i in order.iter() {
+        if distinct.last() != Some(&&points[i][..]) {
+            distinct.push(&points[i]);
+        }
+        distinct_index[i] = distinct.len() - 1;
+    }
+
+    let num_objectives = points.first().map_or(0, |p| p.len());
+    let mut sorter = DivideAndConquer {
+        points: &distinct,
+        ranks: vec![0; distinct.len()],
+    };
+    let all: Vec<usize> = (0..distinct.len()).collect();
+    sorter.helper_a(&all, num_objectives);
+
+    distinct_index.iter().map(|&i| sorter.ranks[i]).collect()
+}
+
+/// The state of the divide-and-conquer sort.
+///
+/// Points are referred to by their index into `points`, which are
+/// distinct and sorted lexicographically. Hence, a point can only be
+/// dominated by points with a lower index, and sets of points are kept
+/// sorted by index. `k` is the number of leading objectives that are
+/// considered.
+struct DivideAndConquer<'p> {
+    points: &'p [&'p [usize]],
+    ranks: Vec<usize>,
+}
+
+impl<'p> DivideAndConquer<'p> {
+    fn weakly_dominates(&self, a: usize, b: usize, k: usize) -> bool {
+        self.points[a][..k]
+            .iter()
+            .zip(self.points[b][..k].iter())
+            .all(|(x, y)| x <= y)
+    }
+
+    /// `b` is dominated by `a`.
+    fn update(&mut self, a: usize, b: usize) {
+        self.ranks[b] = self.ranks[b].max(self.ranks[a] + 1);
+    }
+
+    fn value(&self, i: usize, k: usize) -> usize {
+        self.points[i][k - 1]
+    }
+
+    fn median(&self, sets: &[&[usize]], k: usize) -> usize {
+        let mut values: Vec<usize> = sets
+            .iter()
+            .flat_map(|set| set.iter().map(|&i| self.value(i, k)))
+            .collect();
+        let mid = values.len() / 2;
+        *values.select_nth_unstable(mid).1
+    }
+
+    /// Splits `set` into the points with a value of objective `k` less
+    /// than, equal to and greater than `median`.
+    fn split(&self, set: &[usize], median: usize, k: usize) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+        let (mut less, mut equal, mut greater) = (Vec::new(), Vec::new(), Vec::new());
+        for &i in set.iter() {
+            match self.value(i, k).cmp(&median) {
+                Ordering::Less => less.push(i),
+                Ordering::Equal => equal.push(i),
+                Ordering::Greater => greater.push(i),
+            }
+        }
+        (less, equal, greater)
+    }
+
+    /// Assigns the final ranks to the points of `set`, which agree in
+    /// all but the first `k` objectives and whose ranks are final with
+    /// respect to all points outside of `set`.
+    fn helper_a(&mut self, set: &[usize], k: usize) {
+        if set.len() < 2 {
+            return;
+        }
+
+        if k == 1 {
+            // The points form a chain.
+            for w in set.windows(2) {
+                self.update(w[0], w[1]);
+            }
+        } else if set.len() == 2 {
+            if self.weakly_dominates(set[0], set[1], k) {
+                self.update(set[0], set[1]);
+            }
+        } else if k == 2 {
+            self.sweep_a(set);
+        } else if set.iter().all(|&i| self.value(i, k) == self.value(set[0], k)) {
+            self.helper_a(set, k - 1);
+        } else {
+            let median = self.median(&[set], k);
+            let (less, equal, greater) = self.split(set, median, k);
+            self.helper_a(&less, k);
+            self.helper_b(&less, &equal, k - 1);
+            self.helper_a(&equal, k - 1);
+            let less_equal = merge(&less, &equal);
+            self.helper_b(&less_equal, &greater, k - 1);
+            self.helper_a(&greater, k);
+        }
+    }
+
+    /// Updates the ranks of the points of `high` by those of `low`,
+    /// which are final. Each point of `low` is not worse than each point
+    /// of `high` in all but the first `k` objectives.
+    fn helper_b(&mut self, low: &[usize], high: &[usize], k: usize) {
+        if low.is_empty() || high.is_empty() {
+            return;
+        }
+
+        if low.len() == 1 || high.len() == 1 {
+            for &h in high.iter() {
+                for &l in low.iter() {
+                    if self.weakly_dominates(l, h, k) {
+                        self.update(l, h);
+                    }
+                }
+            }
+            return;
+        }
+
+        if k == 2 {
+            self.sweep_b(low, high);
+            return;
+        }
+
+        let low_min = low.iter().map(|&i| self.value(i, k)).min().unwrap();
+        let low_max = low.iter().map(|&i| self.value(i, k)).max().unwrap();
+        let high_min = high.iter().map(|&i| self.value(i, k)).min().unwrap();
+        let high_max = high.iter().map(|&i| self.value(i, k)).max().unwrap();
+
+        if low_max <= high_min {
+            self.helper_b(low, high, k - 1);
+        } else if low_min <= high_max {
+            let median = self.median(&[low, high], k);
+            let (low_less, low_equal, low_greater) = self.split(low, median, k);
+            let (high_less, high_equal, high_greater) = self.split(high, median, k);
+            self.helper_b(&low_less, &high_less, k);
+            self.helper_b(&low_less, &high_equal, k - 1);
+            self.helper_b(&low_equal, &high_equal, k - 1);
+            let low_less_equal = merge(&low_less, &low_equal);
+            self.helper_b(&low_less_equal, &high_greater, k - 1);
+            self.helper_b(&low_greater, &high_greater, k);
+        }
+    }
+
+    /// `helper_a` for two objectives.
+    fn sweep_a(&mut self, set: &[usize]) {
+        let mut stairs = BTreeMap::new();
+        for &i in set.iter() {
+            let y = self.value(i, 2);
+            if let Some((_, &rank)) = stairs.range(..=y).next_back() {
+                self.ranks[i] = self.ranks[i].max(rank + 1);
+            }
+            insert_stair(&mut stairs, y, self.ranks[i]);
+        }
+    }
+
+    /// `helper_b` for two objectives.
+    fn sweep_b(&mut self, low: &[usize], high: &[usize]) {
+        let mut stairs = BTreeMap::new();
+        let mut low = low.iter().peekable();
+        for &h in high.iter() {
+            while let Some(&&l) = low.peek() {
+                if l > h {
+                    break;
+                }
+                insert_stair(&mut stairs, self.value(l, 2), self.ranks[l]);
+                low.next();
+            }
+            if let Some((_, &rank)) = stairs.range(..=self.value(h, 2)).next_back() {
+                self.ranks[h] = self.ranks[h].max(rank + 1);
+            }
+        }
+    }
+}
+
+/// Inserts a point with value `y` of the second objective and `rank`
+/// into `stairs`, which maps the second objective to the maximum rank
+/// of all points up to that value. Both keys and ranks are increasing.
+fn insert_stair(stairs: &mut BTreeMap<usize, usize>, y: usize, rank: usize) {
+    if let Some((_, &r)) = stairs.range(..=y).next_back() {
+        if r >= rank {
+            return;
+        }
+    }
+    let obsolete: Vec<usize> = stairs
+        .range(y..)
+        .take_while(|&(_, &r)| r <= rank)
+        .map(|(&key, _)| key)
+        .collect();
+    for key in obsolete {
+        stairs.remove(&key);
+    }
+    stairs.insert(y, rank);
+}
+
+/// Merges two sets of points, each sorted by index.
+fn merge(a: &[usize], b: &[usize]) -> Vec<usize> {
+    let mut result = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] < b[j] {
+            result.push(a[i]);
+            i += 1;
+        } else {
+            result.push(b[j]);
+            j += 1;
+        }
+    }
+    result.extend_from_slice(&a[i..]);
+    result.extend_from_slice(&b[j..]);
+    result
+}
+
+#[cfg(test)]
+fn front_indices<S>(fronts: &[Front<S>]) -> Vec<Vec<usize>> {
+    fronts
+        .iter()
+        .map(|front| {
+            let mut indices = front.solutions_indices_only();
+            indices.sort();
+            indices
+        })
+        .collect()
+}
+
+#[test]
+fn test_sort_fronts() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let solutions = vec![
+        Tuple(1, 2),
+        Tuple(1, 2),
+        Tuple(2, 1),
+        Tuple(1, 3),
+        Tuple(3, 3),
+        Tuple(0, 4),
+    ];
+    let expected = vec![vec![0, 1, 2, 5], vec![3], vec![4]];
+
+    for &algorithm in [
+        SortAlgorithm::Fast,
+        SortAlgorithm::DivideAndConquer,
+        SortAlgorithm::EnsSequential,
+        SortAlgorithm::EnsBinary,
+    ].iter()
+    {
+        let fronts = sort_fronts(algorithm, &solutions, &mo);
+        assert_eq!(expected, front_indices(&fronts));
+        for (rank, front) in fronts.iter().enumerate() {
+            assert_eq!(rank, front.rank);
+        }
+    }
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_sort_algorithms_agree(
+        num_objectives in 1usize..6,
+        values in proptest::collection::vec(0u8..5, 0..300)
+    ) {
+        use objective::Objective;
+        use multi_objective::MultiObjective;
+        use test_helper_objective::{Coordinate, Point};
+
+        // Few distinct values result in many equal objective values.
+        let solutions: Vec<Point> = values
+            .chunks(num_objectives)
+            .filter(|chunk| chunk.len() == num_objectives)
+            .map(|chunk| chunk.iter().map(|&v| v as f64).collect())
+            .collect();
+        let coordinates: Vec<_> = (0..num_objectives).map(Coordinate).collect();
+        let objectives: Vec<&dyn Objective<Solution = Point, Distance = f64>> =
+            coordinates.iter().map(|c| c as &dyn Objective<Solution = Point, Distance = f64>).collect();
+        let mo = MultiObjective::new(&objectives);
+
+        let expected = front_indices(&sort_fronts(SortAlgorithm::Fast, &solutions, &mo));
+        for &algorithm in [
+            SortAlgorithm::DivideAndConquer,
+            SortAlgorithm::EnsSequential,
+            SortAlgorithm::EnsBinary,
+        ].iter()
+        {
+            prop_assert_eq!(&expected, &front_indices(&sort_fronts(algorithm, &solutions, &mo)));
+        }
+    }
+}
@@ -0,0 +1,534 @@
+//! Steady-state `(mu + 1)` NSGA-II.
+//!
+//! Each step creates and rates a single offspring, inserts it into the
+//! population and removes the worst individual by rank and crowding
+//! distance. This suits expensive fitness evaluations, as each offspring
+//! can be bred from the offspring rated just before.
+//!
+//! Instead of sorting the whole population into pareto fronts after
+//! each insertion, the fronts are updated incrementally: only the
+//! individuals dominated by the offspring can change their rank, and
+//! the crowding distance is only recalculated for the fronts that
+//! changed.
+
+use rand::Rng;
+use std::cmp::Ordering;
+use std::time::Instant;
+use non_dominated_sort::{Front, SolutionWithIndex};
+use engine::{breed, Driver, EvoConfig, Individual, PopulationResult};
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use crowding_distance::{assign_crowding_distance, cmp_crowding_distance};
+use nan_worst::NanWorst;
+use selection::{check_nan_policy, SelectionError};
+use observer::Observer;
+use termination::{MaxGenerations, Progress, Termination};
+
+/// A population, which is kept sorted into pareto fronts. The rank and
+/// crowding distance of each individual are always up to date.
+///
+/// Individuals with invalid (e.g. NaN) fitness values are ranked last,
+/// as by `engine::run`.
+#[derive(Clone, Debug)]
+pub struct SteadyStatePopulation<G, F> {
+    individuals: Vec<Individual<G, F>>,
+    /// The indices into `individuals` of each front, best front first.
+    fronts: Vec<Vec<usize>>,
+}
+
+impl<G, F> SteadyStatePopulation<G, F> {
+    /// Sorts `individuals` into pareto fronts and assigns rank and
+    /// crowding distance. Their previous rank and crowding distance are
+    /// ignored.
+    pub fn new<M>(individuals: Vec<Individual<G, F>>, multi_objective: &M) -> Self
+    where
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let multi_objective = NanWorst::new(multi_objective);
+        let mut population = SteadyStatePopulation {
+            individuals: Vec::with_capacity(individuals.len()),
+            fronts: Vec::new(),
+        };
+        for individual in individuals {
+            population.individuals.push(individual);
+            let index = population.individuals.len() - 1;
+            population.insert_into_fronts(index, &multi_objective);
+        }
+        for rank in 0..population.fronts.len() {
+            population.update_crowding_distance(rank, &multi_objective);
+        }
+        population
+    }
+
+    /// The individuals, in no particular order.
+    pub fn individuals(&self) -> &[Individual<G, F>] {
+        &self.individuals
+    }
+
+    pub fn into_individuals(self) -> Vec<Individual<G, F>> {
+        self.individuals
+    }
+
+    pub fn len(&self) -> usize {
+        self.individuals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.individuals.is_empty()
+    }
+
+    /// The number of individuals of each rank, starting with rank 0.
+    pub fn front_sizes(&self) -> Vec<usize> {
+        self.fronts.iter().map(|front| front.len()).collect()
+    }
+
+    /// Inserts `individual` and updates the rank of the individuals it
+    /// dominates, and the crowding distance within the changed fronts.
+    pub fn insert<M>(&mut self, individual: Individual<G, F>, multi_objective: &M)
+    where
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let multi_objective = NanWorst::new(multi_objective);
+        self.individuals.push(individual);
+        let index = self.individuals.len() - 1;
+        let (first, last) = self.insert_into_fronts(index, &multi_objective);
+        for rank in first..last + 1 {
+            self.update_crowding_distance(rank, &multi_objective);
+        }
+    }
+
+    /// Removes and returns the worst individual, the one with the
+    /// smallest crowding distance in the last front. Ties are broken
+    /// deterministically.
+    pub fn remove_worst<M>(&mut self, multi_objective: &M) -> Option<Individual<G, F>>
+    where
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+    {
+        let last_rank = self.fronts.len().checked_sub(1)?;
+        let index = {
+            let individuals = &self.individuals;
+            let last_front = &mut self.fronts[last_rank];
+            let position = (0..last_front.len())
+                .max_by(|&a, &b| {
+                    let (a, b) = (last_front[a], last_front[b]);
+                    cmp_crowding_distance(individuals[a].crowding_distance, individuals[b].crowding_distance)
+                        .then(a.cmp(&b))
+                })
+                .unwrap();
+            last_front.swap_remove(position)
+        };
+
+        // Removing an individual of the last front does not change any
+        // rank.
+        if self.fronts[last_rank].is_empty() {
+            self.fronts.pop();
+        } else {
+            self.update_crowding_distance(last_rank, &NanWorst::new(multi_objective));
+        }
+
+        let removed = self.individuals.swap_remove(index);
+
+        // the previously last individual has moved to `index`.
+        if index < self.individuals.len() {
+            let moved = self.individuals.len();
+            let rank = self.individuals[index].rank;
+            for i in self.fronts[rank].iter_mut() {
+                if *i == moved {
+                    *i = index;
+                }
+            }
+        }
+
+        Some(removed)
+    }
+
+    /// Sorts the individual at `index` into the fronts. Returns the
+    /// first and the last rank of the fronts, that changed.
+    ///
+    /// The individual belongs to the first front, in which no individual
+    /// dominates it. The individuals of that front, which it dominates,
+    /// move to the next front, where they in turn push the individuals
+    /// they dominate to the front after, and so on.
+    fn insert_into_fronts<N>(&mut self, index: usize, multi_objective: &N) -> (usize, usize)
+    where
+        N: ObjectiveSet<Solution = F>,
+    {
+        let individuals = &self.individuals;
+        let dominates = |a: usize, b: usize| {
+            multi_objective.domination_ord(&individuals[a].fitness, &individuals[b].fitness) == Ordering::Less
+        };
+
+        let first = self.fronts
+            .iter()
+            .position(|front| !front.iter().any(|&j| dominates(j, index)))
+            .unwrap_or(self.fronts.len());
+
+        let mut rank = first;
+        let mut moving = vec![index];
+        while !moving.is_empty() {
+            if rank == self.fronts.len() {
+                self.fronts.push(Vec::new());
+            }
+            let (dominated, mut kept): (Vec<usize>, Vec<usize>) = self.fronts[rank]
+                .iter()
+                .partition(|&&j| moving.iter().any(|&m| dominates(m, j)));
+            kept.extend(moving);
+            self.fronts[rank] = kept;
+            moving = dominated;
+            rank += 1;
+        }
+        let last = rank - 1;
+
+        for rank in first..last + 1 {
+            for &i in self.fronts[rank].iter() {
+                self.individuals[i].rank = rank;
+            }
+        }
+
+        (first, last)
+    }
+
+    fn update_crowding_distance<N>(&mut self, rank: usize, multi_objective: &N)
+    where
+        N: ObjectiveSet<Solution = F>,
+        N::Distance: ToF64,
+    {
+        let distances: Vec<(usize, f64)> = {
+            let front = Front {
+                rank,
+                solutions: self.fronts[rank]
+                    .iter()
+                    .map(|&index| SolutionWithIndex {
+                        solution: &self.individuals[index].fitness,
+                        index,
+                    })
+                    .collect(),
+            };
+            assign_crowding_distance(&front, multi_objective)
+                .0
+                .iter()
+                .map(|a| (a.index, a.crowding_distance))
+                .collect()
+        };
+
+        for (index, crowding_distance) in distances {
+            self.individuals[index].crowding_distance = crowding_distance;
+        }
+    }
+}
+
+/// Performs one step of the steady-state NSGA-II.
+///
+/// Creates a single offspring from parents chosen by tournament
+/// selection (using the crowded-comparison operator), rates it, inserts
+/// it into `population` and removes the worst individuals until there
+/// are `mu` left. The `lambda` of `evo_config` is ignored.
+///
+/// Fails if `population` is empty, or with `NanPolicy::Reject` if the
+/// fitness of the offspring is invalid. The offspring then counts as the
+/// solution after the population, and is not inserted.
+pub fn step<R, D, M>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    multi_objective: &M,
+    population: &mut SteadyStatePopulation<D::Genome, D::Fitness>,
+) -> Result<(), SelectionError>
+where
+    R: Rng,
+    D: Driver,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+{
+    if population.is_empty() {
+        return Err(SelectionError::NoSolutions);
+    }
+    let child = breed(rng, driver, evo_config, population.individuals());
+    let fitness = driver.fitness(&child);
+    check_nan_policy(population.len(), &fitness, multi_objective, evo_config.nan_policy)?;
+
+    population.insert(
+        Individual {
+            genome: child,
+            fitness,
+            rank: 0,
+            crowding_distance: 0.0,
+        },
+        multi_objective,
+    );
+    while population.len() > evo_config.mu {
+        population.remove_worst(multi_objective);
+    }
+    Ok(())
+}
+
+/// Runs the steady-state NSGA-II for `ngen` steps, each rating a single
+/// offspring, and returns the final ranked population.
+///
+/// Fails like `engine::run`: if `mu` is 0, or with `NanPolicy::Reject`
+/// as soon as a fitness value is invalid.
+pub fn run<R, D, M>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    multi_objective: &M,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+{
+    let mut termination = MaxGenerations(evo_config.ngen);
+    run_until(rng, driver, evo_config, multi_objective, &mut termination, &mut ())
+}
+
+/// Like `run`, but runs until `termination` is met. The `ngen` of
+/// `evo_config` is ignored.
+///
+/// `observer` and then `termination` are called after the initial
+/// population is created and after each step. Each step counts as a
+/// generation.
+pub fn run_until<R, D, M, T, O>(
+    rng: &mut R,
+    driver: &D,
+    evo_config: &EvoConfig,
+    multi_objective: &M,
+    termination: &mut T,
+    observer: &mut O,
+) -> PopulationResult<D::Genome, D::Fitness>
+where
+    R: Rng,
+    D: Driver,
+    M: ObjectiveSet<Solution = D::Fitness>,
+    M::Distance: ToF64,
+    T: Termination<D::Genome, D::Fitness> + ?Sized,
+    O: Observer<D::Genome, D::Fitness> + ?Sized,
+{
+    let start = Instant::now();
+    if evo_config.mu == 0 {
+        return Err(SelectionError::NoSolutions);
+    }
+    let genomes: Vec<_> = (0..evo_config.mu)
+        .map(|_| driver.random_genome(rng))
+        .collect();
+    let fitness = driver.rate(&genomes);
+    for (index, fitness) in fitness.iter().enumerate() {
+        check_nan_policy(index, fitness, multi_objective, evo_config.nan_policy)?;
+    }
+    let individuals = genomes
+        .into_iter()
+        .zip(fitness)
+        .map(|(genome, fitness)| Individual {
+            genome,
+            fitness,
+            rank: 0,
+            crowding_distance: 0.0,
+        })
+        .collect();
+
+    let mut population = SteadyStatePopulation::new(individuals, multi_objective);
+
+    let mut progress = Progress::new(start, 0, evo_config.mu);
+    while !progress.is_finished(population.individuals(), multi_objective, termination, observer) {
+        step(rng, driver, evo_config, multi_objective, &mut population)?;
+        progress.next_generation(1);
+    }
+
+    Ok(population.into_individuals())
+}
+
+#[cfg(test)]
+fn individual(fitness: ::test_helper_objective::Tuple) -> Individual<(), ::test_helper_objective::Tuple> {
+    Individual {
+        genome: (),
+        fitness,
+        rank: 0,
+        crowding_distance: 0.0,
+    }
+}
+
+#[test]
+fn test_steady_state_population() {
+    use multi_objective::MultiObjective;
+    use test_helper_objective::{Objective1, Objective2, Tuple};
+
+    let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+    let ranks = |population: &SteadyStatePopulation<(), Tuple>| -> Vec<(usize, usize, usize)> {
+        let mut ranks: Vec<_> = population
+            .individuals()
+            .iter()
+            .map(|i| (i.fitness.0, i.fitness.1, i.rank))
+            .collect();
+        ranks.sort();
+        ranks
+    };
+
+    let mut population = SteadyStatePopulation::new(vec![individual(Tuple(2, 2)), individual(Tuple(3, 3))], &mo);
+    assert_eq!(vec![(2, 2, 0), (3, 3, 1)], ranks(&population));
+
+    // (1, 1) pushes both down by one front.
+    population.insert(individual(Tuple(1, 1)), &mo);
+    assert_eq!(vec![(1, 1, 0), (2, 2, 1), (3, 3, 2)], ranks(&population));
+    assert_eq!(vec![1, 1, 1], population.front_sizes());
+
+    // (0, 4) is non-dominated and dominates nothing.
+    population.insert(individual(Tuple(0, 4)), &mo);
+    assert_eq!(vec![2, 1, 1], population.front_sizes());
+    assert_eq!(
+        f64::INFINITY,
+        population.individuals().iter().find(|i| i.fitness == Tuple(1, 1)).unwrap().crowding_distance
+    );
+
+    assert_eq!(Tuple(3, 3), population.remove_worst(&mo).unwrap().fitness);
+    assert_eq!(Tuple(2, 2), population.remove_worst(&mo).unwrap().fitness);
+    assert_eq!(vec![(0, 4, 0), (1, 1, 0)], ranks(&population));
+    population.remove_worst(&mo);
+    population.remove_worst(&mo);
+    assert!(population.is_empty());
+    assert!(population.remove_worst(&mo).is_none());
+}
+
+#[cfg(test)]
+proptest! {
+    #[test]
+    fn prop_incremental_fronts_agree(
+        values in proptest::collection::vec((0usize..6, 0usize..6), 1..60),
+        removals in 0usize..30
+    ) {
+        use multi_objective::MultiObjective;
+        use sorting::{sort_fronts, SortAlgorithm};
+        use test_helper_objective::{Objective1, Objective2, Tuple};
+
+        let mo = MultiObjective::<Tuple, f64>::new(&[&Objective1, &Objective2]);
+
+        let (initial, inserted) = values.split_at(values.len() / 2);
+        let mut population = SteadyStatePopulation::new(
+            initial.iter().map(|&(x, y)| individual(Tuple(x, y))).collect(),
+            &mo,
+        );
+        for &(x, y) in inserted.iter() {
+            population.insert(individual(Tuple(x, y)), &mo);
+        }
+        for _ in 0..removals {
+            population.remove_worst(&mo);
+        }
+
+        let fitness: Vec<Tuple> = population.individuals().iter().map(|i| i.fitness).collect();
+        let mut expected = vec![0; fitness.len()];
+        for front in sort_fronts(SortAlgorithm::Fast, &fitness, &mo) {
+            for s in front.solutions {
+                expected[s.index] = front.rank;
+            }
+        }
+        let actual: Vec<usize> = population.individuals().iter().map(|i| i.rank).collect();
+        prop_assert_eq!(expected, actual);
+    }
+}
+
+#[test]
+fn test_run_steady_state() {
+    use rand::{SeedableRng, XorShiftRng};
+    use observer::GenerationStats;
+    use selection::NanPolicy;
+    use test_helper_objective::SquaresDriver;
+    use vector_objective::VectorMultiObjective;
+
+    let driver = SquaresDriver::default();
+    struct Evaluations(Vec<usize>);
+
+    impl<G, F> Observer<G, F> for Evaluations {
+        fn observe(&mut self, stats: &GenerationStats<G, F>) {
+            assert_eq!(20, stats.population.len());
+            self.0.push(stats.evaluations);
+        }
+    }
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let evo_config = EvoConfig {
+        mu: 20,
+        lambda: 20,
+        k: 2,
+        ngen: 400,
+        nan_policy: NanPolicy::Worst,
+    };
+    let mo = VectorMultiObjective::minimize(2);
+
+    let mut evaluations = Evaluations(Vec::new());
+    let population = run_until(
+        &mut rng,
+        &driver,
+        &evo_config,
+        &mo,
+        &mut MaxGenerations(evo_config.ngen),
+        &mut evaluations,
+    )
+    .unwrap();
+    assert_eq!((20..421).collect::<Vec<_>>(), evaluations.0);
+
+    assert_eq!(20, population.len());
+    for individual in population.iter() {
+        assert_eq!(0, individual.rank);
+        assert!(individual.genome >= 0.0 && individual.genome <= 2.0);
+    }
+}
+
+#[test]
+fn test_run_steady_state_errors() {
+    use rand::{SeedableRng, XorShiftRng};
+    use multi_objective::MultiObjective;
+    use objective::Objective;
+    use selection::NanPolicy;
+    use test_helper_objective::{Coordinate, PartialDriver, Point};
+
+    let mut rng = XorShiftRng::from_seed([1, 2, 3, 4]);
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+    let evo_config = EvoConfig {
+        mu: 20,
+        lambda: 1,
+        k: 2,
+        ngen: 100,
+        nan_policy: NanPolicy::Worst,
+    };
+
+    // Invalid individuals are ranked last, and are removed.
+    let population = run(&mut rng, &PartialDriver(0.0), &evo_config, &mo).unwrap();
+    assert_eq!(20, population.len());
+    assert!(population.iter().all(|i| i.rank == 0 && i.genome >= 0.0));
+
+    // Some of the random genomes are negative.
+    let reject = EvoConfig {
+        nan_policy: NanPolicy::Reject,
+        ..evo_config.clone()
+    };
+    let result = run(&mut rng, &PartialDriver(0.0), &reject, &mo);
+    assert!(matches!(result, Err(SelectionError::InvalidValue { .. })));
+
+    // An invalid offspring is not inserted.
+    let mut population = SteadyStatePopulation::new(Vec::new(), &mo);
+    let result = step(&mut rng, &PartialDriver(0.0), &reject, &mo, &mut population);
+    assert_eq!(Err(SelectionError::NoSolutions), result);
+    population.insert(
+        Individual {
+            genome: -1.0,
+            fitness: vec![1.0, 1.0],
+            rank: 0,
+            crowding_distance: 0.0,
+        },
+        &mo,
+    );
+    let result = step(&mut rng, &PartialDriver(f64::INFINITY), &reject, &mo, &mut population);
+    assert_eq!(Err(SelectionError::InvalidValue { solution: 1, objective: 0 }), result);
+    assert_eq!(1, population.len());
+
+    let empty = EvoConfig {
+        mu: 0,
+        ..evo_config
+    };
+    let result = run(&mut rng, &PartialDriver(0.0), &empty, &mo);
+    assert!(matches!(result, Err(SelectionError::NoSolutions)));
+}
@@ -0,0 +1,367 @@
+//! Conditions to terminate an evolution run.
+//!
+//! A `Termination` is checked by `engine::run_until` after the initial
+//! population is created and after each generation. Conditions can be
+//! combined with `Any` and `All`.
+
+use std::time::{Duration, Instant};
+use engine::Individual;
+use multi_objective::ObjectiveSet;
+use objective::ToF64;
+use observer::{GenerationStats, Observer};
+use indicator::hypervolume::hypervolume;
+
+/// A condition to terminate an evolution run.
+pub trait Termination<G, F> {
+    /// Whether the run should terminate after the generation described
+    /// by `stats`. Called once per generation, so that stateful
+    /// conditions can track the progress of the run.
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool;
+}
+
+impl<G, F, T> Termination<G, F> for Box<T>
+where
+    T: Termination<G, F> + ?Sized,
+{
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        (**self).is_met(stats)
+    }
+}
+
+impl<G, F, T> Termination<G, F> for &mut T
+where
+    T: Termination<G, F> + ?Sized,
+{
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        (**self).is_met(stats)
+    }
+}
+
+/// Terminates after the given number of generations.
+#[derive(Clone, Debug)]
+pub struct MaxGenerations(pub usize);
+
+impl<G, F> Termination<G, F> for MaxGenerations {
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        stats.generation >= self.0
+    }
+}
+
+/// Terminates as soon as the given number of fitness evaluations has
+/// been performed. As a generation is never interrupted, the number of
+/// evaluations can exceed the budget by up to `lambda - 1`.
+#[derive(Clone, Debug)]
+pub struct MaxEvaluations(pub usize);
+
+impl<G, F> Termination<G, F> for MaxEvaluations {
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        stats.evaluations >= self.0
+    }
+}
+
+/// Terminates as soon as the given wall-clock time has elapsed since the
+/// start of the run.
+#[derive(Clone, Debug)]
+pub struct TimeBudget(pub Duration);
+
+impl<G, F> Termination<G, F> for TimeBudget {
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        stats.total_time >= self.0
+    }
+}
+
+/// Terminates if the hypervolume of the first front with respect to
+/// `reference` has not improved by more than `tolerance` for
+/// `generations` generations.
+pub struct HypervolumeStagnation<'m, M>
+where
+    M: ObjectiveSet + 'm,
+{
+    pub multi_objective: &'m M,
+    pub reference: M::Solution,
+    pub generations: usize,
+    pub tolerance: f64,
+    best: Option<f64>,
+    stagnant: usize,
+}
+
+impl<'m, M> HypervolumeStagnation<'m, M>
+where
+    M: ObjectiveSet + 'm,
+    M::Distance: ToF64,
+{
+    pub fn new(multi_objective: &'m M, reference: M::Solution, generations: usize, tolerance: f64) -> Self {
+        assert!(tolerance >= 0.0);
+        HypervolumeStagnation {
+            multi_objective,
+            reference,
+            generations,
+            tolerance,
+            best: None,
+            stagnant: 0,
+        }
+    }
+}
+
+impl<'m, G, M> Termination<G, M::Solution> for HypervolumeStagnation<'m, M>
+where
+    M: ObjectiveSet + 'm,
+    M::Distance: ToF64,
+    M::Solution: Clone,
+{
+    fn is_met(&mut self, stats: &GenerationStats<G, M::Solution>) -> bool {
+        let front: Vec<_> = stats.first_front().map(|i| i.fitness.clone()).collect();
+        let hv = hypervolume(self.multi_objective, &front, &self.reference);
+
+        match self.best {
+            Some(best) if hv <= best + self.tolerance => {
+                self.stagnant += 1;
+            }
+            _ => {
+                self.best = Some(hv);
+                self.stagnant = 0;
+            }
+        }
+        self.stagnant >= self.generations
+    }
+}
+
+/// Terminates as soon as an indicator, calculated by `indicator` from
+/// the statistics of a generation, reaches `target`.
+pub struct IndicatorTarget<I> {
+    pub indicator: I,
+    pub target: f64,
+    /// Whether smaller values of the indicator are better (e.g. the
+    /// inverted generational distance), or larger (e.g. the
+    /// hypervolume).
+    pub minimize: bool,
+}
+
+impl<I> IndicatorTarget<I> {
+    /// Terminates if the indicator is less than or equal to `target`.
+    pub fn at_most(indicator: I, target: f64) -> Self {
+        IndicatorTarget {
+            indicator,
+            target,
+            minimize: true,
+        }
+    }
+
+    /// Terminates if the indicator is greater than or equal to `target`.
+    pub fn at_least(indicator: I, target: f64) -> Self {
+        IndicatorTarget {
+            indicator,
+            target,
+            minimize: false,
+        }
+    }
+}
+
+impl<G, F, I> Termination<G, F> for IndicatorTarget<I>
+where
+    I: FnMut(&GenerationStats<G, F>) -> f64,
+{
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        let value = (self.indicator)(stats);
+        if self.minimize {
+            value <= self.target
+        } else {
+            value >= self.target
+        }
+    }
+}
+
+/// Terminates if the user-supplied predicate returns true.
+pub struct Predicate<P>(pub P);
+
+impl<G, F, P> Termination<G, F> for Predicate<P>
+where
+    P: FnMut(&GenerationStats<G, F>) -> bool,
+{
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        (self.0)(stats)
+    }
+}
+
+/// Terminates if any of the conditions is met. All conditions are
+/// checked every generation.
+pub struct Any<'t, G, F>(pub Vec<Box<dyn Termination<G, F> + 't>>);
+
+impl<'t, G, F> Termination<G, F> for Any<'t, G, F> {
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        let mut met = false;
+        for condition in self.0.iter_mut() {
+            met |= condition.is_met(stats);
+        }
+        met
+    }
+}
+
+/// Terminates if all of the conditions are met. All conditions are
+/// checked every generation.
+pub struct All<'t, G, F>(pub Vec<Box<dyn Termination<G, F> + 't>>);
+
+impl<'t, G, F> Termination<G, F> for All<'t, G, F> {
+    fn is_met(&mut self, stats: &GenerationStats<G, F>) -> bool {
+        let mut met = true;
+        for condition in self.0.iter_mut() {
+            met &= condition.is_met(stats);
+        }
+        met
+    }
+}
+
+/// The progress of a run: the number of generations and evaluations
+/// performed, and the time spent. Used by the run loops of all
+/// algorithms, which look like
+///
+/// ```ignore
+/// let start = Instant::now();
+/// // create and rate the initial population of `mu` individuals
+/// let mut progress = Progress::new(start, 0, mu);
+/// while !progress.is_finished(&population, multi_objective, termination, observer) {
+///     // perform a generation, with `evaluations` evaluations
+///     progress.next_generation(evaluations);
+/// }
+/// ```
+pub(crate) struct Progress {
+    generation: usize,
+    evaluations: usize,
+    start: Instant,
+    generation_start: Instant,
+}
+
+impl Progress {
+    /// Starts (or resumes) a run after `generation` generations and
+    /// `evaluations` evaluations. The run, and its first generation,
+    /// started at `start`, so that the time to create the initial
+    /// population counts, too.
+    pub(crate) fn new(start: Instant, generation: usize, evaluations: usize) -> Self {
+        Progress {
+            generation,
+            evaluations,
+            start,
+            generation_start: start,
+        }
+    }
+
+    /// Calls `observer` and then `termination` with the statistics of
+    /// `population`, and returns whether the run is finished. If not,
+    /// the time of the next generation starts.
+    pub(crate) fn is_finished<G, F, M, T, O>(
+        &mut self,
+        population: &[Individual<G, F>],
+        multi_objective: &M,
+        termination: &mut T,
+        observer: &mut O,
+    ) -> bool
+    where
+        M: ObjectiveSet<Solution = F>,
+        M::Distance: ToF64,
+        T: Termination<G, F> + ?Sized,
+        O: Observer<G, F> + ?Sized,
+    {
+        let stats = GenerationStats::new(
+            self.generation,
+            self.evaluations,
+            population,
+            multi_objective,
+            self.generation_start.elapsed(),
+            self.start.elapsed(),
+        );
+        observer.observe(&stats);
+        if termination.is_met(&stats) {
+            return true;
+        }
+
+        self.generation_start = Instant::now();
+        false
+    }
+
+    /// Counts a generation, which performed `evaluations` evaluations.
+    pub(crate) fn next_generation(&mut self, evaluations: usize) {
+        self.generation += 1;
+        self.evaluations += evaluations;
+    }
+}
+
+#[cfg(test)]
+fn stats_of<'a>(
+    generation: usize,
+    population: &'a [::engine::Individual<(), ::test_helper_objective::Point>],
+) -> GenerationStats<'a, (), ::test_helper_objective::Point> {
+    use multi_objective::MultiObjective;
+    use objective::Objective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    GenerationStats::new(
+        generation,
+        10 * (generation + 1),
+        population,
+        &MultiObjective::new(&objectives),
+        Duration::from_secs(1),
+        Duration::from_secs(generation as u64 + 1),
+    )
+}
+
+#[test]
+fn test_simple_terminations() {
+    let population = vec![];
+    let stats = stats_of(4, &population);
+
+    assert!(MaxGenerations(4).is_met(&stats));
+    assert!(!MaxGenerations(5).is_met(&stats));
+    assert!(MaxEvaluations(50).is_met(&stats));
+    assert!(!MaxEvaluations(51).is_met(&stats));
+    assert!(TimeBudget(Duration::from_secs(5)).is_met(&stats));
+    assert!(!TimeBudget(Duration::from_secs(6)).is_met(&stats));
+
+    assert!(IndicatorTarget::at_most(|s: &GenerationStats<_, _>| s.generation as f64, 4.0).is_met(&stats));
+    assert!(!IndicatorTarget::at_most(|s: &GenerationStats<_, _>| s.generation as f64, 3.0).is_met(&stats));
+    assert!(IndicatorTarget::at_least(|s: &GenerationStats<_, _>| s.generation as f64, 4.0).is_met(&stats));
+    assert!(!IndicatorTarget::at_least(|s: &GenerationStats<_, _>| s.generation as f64, 5.0).is_met(&stats));
+
+    assert!(Predicate(|s: &GenerationStats<_, _>| s.population.is_empty()).is_met(&stats));
+
+    let mut any: Any<(), _> = Any(vec![Box::new(MaxGenerations(10)), Box::new(MaxEvaluations(50))]);
+    assert!(any.is_met(&stats));
+    let mut all: All<(), _> = All(vec![Box::new(MaxGenerations(10)), Box::new(MaxEvaluations(50))]);
+    assert!(!all.is_met(&stats));
+    let mut all: All<(), _> = All(vec![Box::new(MaxGenerations(4)), Box::new(MaxEvaluations(50))]);
+    assert!(all.is_met(&stats));
+}
+
+#[test]
+fn test_hypervolume_stagnation() {
+    use engine::Individual;
+    use multi_objective::MultiObjective;
+    use objective::Objective;
+    use test_helper_objective::{Coordinate, Point};
+
+    let objectives: [&dyn Objective<Solution = Point, Distance = f64>; 2] = [&Coordinate(0), &Coordinate(1)];
+    let mo = MultiObjective::new(&objectives);
+    let mut stagnation = HypervolumeStagnation::new(&mo, vec![4.0, 4.0], 2, 0.1);
+
+    let individual = |x: f64, y: f64| Individual {
+        genome: (),
+        fitness: vec![x, y],
+        rank: 0,
+        crowding_distance: 0.0,
+    };
+
+    // hypervolumes 1, 4, 4.05 (no improvement), 4.05, 9
+    let populations = [
+        vec![individual(3.0, 3.0)],
+        vec![individual(2.0, 2.0)],
+        vec![individual(2.0, 2.0), individual(1.0, 3.95)],
+        vec![individual(2.0, 2.0), individual(1.0, 3.95)],
+        vec![individual(1.0, 1.0)],
+    ];
+    let met: Vec<_> = populations
+        .iter()
+        .enumerate()
+        .map(|(generation, population)| stagnation.is_met(&stats_of(generation, population)))
+        .collect();
+    assert_eq!(vec![false, false, false, true, false], met);
+}
@@ -1,4 +1,6 @@
 use std::cmp::Ordering;
+use rand::Rng;
+use engine::Driver;
 use objective::Objective;
 
 // Our multi-variate fitness/solution value
@@ -49,3 +51,102 @@ impl Objective for Objective3 {
         (a.0 + a.1) as f64 - (b.0 + b.1) as f64
     }
 }
+
+// A point in objective space and an objective for each coordinate
+pub type Point = Vec<f64>;
+
+pub struct Coordinate(pub usize);
+
+impl Objective for Coordinate {
+    type Solution = Point;
+    type Distance = f64;
+
+    fn total_order(&self, a: &Self::Solution, b: &Self::Solution) -> Ordering {
+        a[self.0].partial_cmp(&b[self.0]).unwrap()
+    }
+
+    fn distance(&self, a: &Self::Solution, b: &Self::Solution) -> Self::Distance {
+        a[self.0] - b[self.0]
+    }
+
+    fn is_valid(&self, solution: &Self::Solution) -> bool {
+        !solution[self.0].is_nan()
+    }
+}
+
+// The fitness is the genome, a pair of values in 0..10. An offspring
+// takes the first value of one parent and the second of the other.
+pub struct PairDriver;
+
+impl Driver for PairDriver {
+    type Genome = (usize, usize);
+    type Fitness = Tuple;
+
+    fn random_genome<R: Rng>(&self, rng: &mut R) -> (usize, usize) {
+        (rng.gen_range(0, 10), rng.gen_range(0, 10))
+    }
+
+    fn fitness(&self, &(x, y): &(usize, usize)) -> Tuple {
+        Tuple(x, y)
+    }
+
+    fn mate<R: Rng>(&self, _rng: &mut R, parent1: &(usize, usize), parent2: &(usize, usize)) -> (usize, usize) {
+        (parent1.0, parent2.1)
+    }
+}
+
+// Minimize (x^2, (x - 2)^2). The pareto optimal solutions are
+// 0 <= x <= 2. An offspring lies between it's parents, plus up to
+// `noise` in either direction.
+#[derive(Default)]
+pub struct SquaresDriver {
+    pub noise: f64,
+}
+
+impl Driver for SquaresDriver {
+    type Genome = f64;
+    type Fitness = (f64, f64);
+
+    fn random_genome<R: Rng>(&self, rng: &mut R) -> f64 {
+        rng.gen_range(-10.0, 10.0)
+    }
+
+    fn fitness(&self, x: &f64) -> (f64, f64) {
+        (x * x, (x - 2.0) * (x - 2.0))
+    }
+
+    fn mate<R: Rng>(&self, rng: &mut R, parent1: &f64, parent2: &f64) -> f64 {
+        let a: f64 = rng.gen();
+        let child = a * parent1 + (1.0 - a) * parent2;
+        if self.noise > 0.0 {
+            child + rng.gen_range(-self.noise, self.noise)
+        } else {
+            child
+        }
+    }
+}
+
+// Like `SquaresDriver` with a noise of 0.5, but the fitness is
+// undefined (NaN) for x less than the given bound.
+pub struct PartialDriver(pub f64);
+
+impl Driver for PartialDriver {
+    type Genome = f64;
+    type Fitness = Point;
+
+    fn random_genome<R: Rng>(&self, rng: &mut R) -> f64 {
+        rng.gen_range(-10.0, 10.0)
+    }
+
+    fn fitness(&self, x: &f64) -> Point {
+        if *x < self.0 {
+            vec![f64::NAN, f64::NAN]
+        } else {
+            vec![x * x, (x - 2.0) * (x - 2.0)]
+        }
+    }
+
+    fn mate<R: Rng>(&self, rng: &mut R, parent1: &f64, parent2: &f64) -> f64 {
+        SquaresDriver { noise: 0.5 }.mate(rng, parent1, parent2)
+    }
+}
@@ -7,7 +7,6 @@ use rand::Rng;
 /// expensive.  Instead we call `rng.choose()` k-times. The drawn items
 /// could be the same for each call, but the probability is very low if
 /// the number of `values` is high compared to `k`.
-
 #[inline]
 pub fn tournament_selection_fast<'a, T, R: Rng, F>(
     rng: &mut R,
@@ -18,7 +17,7 @@ pub fn tournament_selection_fast<'a, T, R: Rng, F>(
 where
     F: Fn(&'a T, &'a T) -> bool,
 {
-    assert!(values.len() > 0);
+    assert!(!values.is_empty());
 
     let mut best = rng.choose(values).unwrap();
 
@@ -29,5 +28,5 @@ where
         }
     }
 
-    return best;
+    best
 }
@@ -0,0 +1,246 @@
+//! Objectives on vector-valued fitness.
+//!
+//! Most fitness values are simply a vector, array or tuple of numbers,
+//! one per objective. Instead of implementing an `Objective` for each
+//! component by hand, use a `VectorObjective` for a single component, or
+//! a `VectorMultiObjective` for all of them.
+
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use objective::{nan_worst_cmp, Direction, Objective, ToF64};
+use multi_objective::{pareto_dominance, ObjectiveSet};
+use non_dominated_sort::DominationOrd;
+
+/// A fitness value made up of a number of components.
+pub trait ObjectiveValues {
+    /// The number of components.
+    fn num_values(&self) -> usize;
+
+    /// The `i`-th component.
+    fn value(&self, i: usize) -> f64;
+}
+
+impl<T: Copy + ToF64> ObjectiveValues for [T] {
+    fn num_values(&self) -> usize {
+        self.len()
+    }
+
+    fn value(&self, i: usize) -> f64 {
+        self[i].to_f64()
+    }
+}
+
+impl<T: Copy + ToF64> ObjectiveValues for Vec<T> {
+    fn num_values(&self) -> usize {
+        self.len()
+    }
+
+    fn value(&self, i: usize) -> f64 {
+        self[i].to_f64()
+    }
+}
+
+impl<T: Copy + ToF64, const N: usize> ObjectiveValues for [T; N] {
+    fn num_values(&self) -> usize {
+        N
+    }
+
+    fn value(&self, i: usize) -> f64 {
+        self[i].to_f64()
+    }
+}
+
+macro_rules! tuple_objective_values {
+    ($len:expr; $($index:tt: $t:ident),+) => {
+        impl<$($t: Copy + ToF64),+> ObjectiveValues for ($($t,)+) {
+            fn num_values(&self) -> usize {
+                $len
+            }
+
+            fn value(&self, i: usize) -> f64 {
+                match i {
+                    $($index => self.$index.to_f64(),)+
+                    _ => panic!("index {} out of bounds for a tuple of {} values", i, $len),
+                }
+            }
+        }
+    };
+}
+
+tuple_objective_values!(1; 0: A);
+tuple_objective_values!(2; 0: A, 1: B);
+tuple_objective_values!(3; 0: A, 1: B, 2: C);
+tuple_objective_values!(4; 0: A, 1: B, 2: C, 3: D);
+tuple_objective_values!(5; 0: A, 1: B, 2: C, 3: D, 4: E);
+tuple_objective_values!(6; 0: A, 1: B, 2: C, 3: D, 4: E, 5: F);
+
+/// The objective of minimizing or maximizing the `index`-th component of
+/// a fitness value.
+pub struct VectorObjective<S> {
+    pub index: usize,
+    pub direction: Direction,
+    _solution: PhantomData<fn(&S)>,
+}
+
+impl<S> VectorObjective<S> {
+    pub fn new(index: usize, direction: Direction) -> Self {
+        VectorObjective {
+            index,
+            direction,
+            _solution: PhantomData,
+        }
+    }
+
+    pub fn minimize(index: usize) -> Self {
+        Self::new(index, Direction::Minimize)
+    }
+
+    pub fn maximize(index: usize) -> Self {
+        Self::new(index, Direction::Maximize)
+    }
+}
+
+impl<S: ObjectiveValues> Objective for VectorObjective<S> {
+    type Solution = S;
+    type Distance = f64;
+
+    /// NaN is the worst value in either direction.
+    fn total_order(&self, a: &S, b: &S) -> Ordering {
+        let (a, b) = (a.value(self.index), b.value(self.index));
+        if a.is_nan() || b.is_nan() {
+            nan_worst_cmp(a, b)
+        } else {
+            self.direction.order(nan_worst_cmp(a, b))
+        }
+    }
+
+    fn distance(&self, a: &S, b: &S) -> f64 {
+        let distance = a.value(self.index) - b.value(self.index);
+        match self.direction {
+            Direction::Minimize => distance,
+            Direction::Maximize => -distance,
+        }
+    }
+
+    fn is_valid(&self, solution: &S) -> bool {
+        !solution.value(self.index).is_nan()
+    }
+}
+
+/// The pareto dominance of all components of a fitness value, each one
+/// to be minimized or maximized.
+pub struct VectorMultiObjective<S> {
+    objectives: Vec<VectorObjective<S>>,
+}
+
+impl<S> VectorMultiObjective<S> {
+    /// One objective per component, in the given `directions`.
+    pub fn new(directions: &[Direction]) -> Self {
+        VectorMultiObjective {
+            objectives: directions
+                .iter()
+                .enumerate()
+                .map(|(index, &direction)| VectorObjective::new(index, direction))
+                .collect(),
+        }
+    }
+
+    /// Minimizes the first `num_objectives` components.
+    pub fn minimize(num_objectives: usize) -> Self {
+        Self::new(&vec![Direction::Minimize; num_objectives])
+    }
+
+    /// Maximizes the first `num_objectives` components.
+    pub fn maximize(num_objectives: usize) -> Self {
+        Self::new(&vec![Direction::Maximize; num_objectives])
+    }
+
+    pub fn objectives(&self) -> &[VectorObjective<S>] {
+        &self.objectives
+    }
+}
+
+impl<S: ObjectiveValues> ObjectiveSet for VectorMultiObjective<S> {
+    type Distance = f64;
+
+    fn num_objectives(&self) -> usize {
+        self.objectives.len()
+    }
+
+    fn objective(&self, i: usize) -> &dyn Objective<Solution = S, Distance = f64> {
+        &self.objectives[i]
+    }
+
+    fn is_pareto_dominance(&self) -> bool {
+        true
+    }
+}
+
+impl<S: ObjectiveValues> DominationOrd for VectorMultiObjective<S> {
+    type Solution = S;
+
+    fn domination_ord(&self, a: &S, b: &S) -> Ordering {
+        pareto_dominance(self, a, b)
+    }
+}
+
+#[test]
+fn test_objective_values() {
+    assert_eq!(3, vec![1.0f32, 2.0, 3.0].num_values());
+    assert_eq!(2.0, [1u32, 2].value(1));
+    assert_eq!(2, (1.0f32, 2u8).num_values());
+    assert_eq!(3.0, (1i32, 2.0f64, 3.0f32).value(2));
+}
+
+#[test]
+fn test_vector_objective() {
+    use select_nsga::SelectNSGA;
+    use selection::{NanPolicy, SelectAndRank};
+
+    let min = VectorObjective::<(f32, f64)>::minimize(1);
+    let max = VectorObjective::<(f32, f64)>::maximize(1);
+    let a = (0.0, 1.0);
+    let b = (0.0, 3.0);
+
+    assert_eq!(Ordering::Less, min.total_order(&a, &b));
+    assert_eq!(Ordering::Greater, max.total_order(&a, &b));
+    assert_eq!(-2.0, min.distance(&a, &b));
+    assert_eq!(2.0, max.distance(&a, &b));
+
+    // NaN is the worst value in either direction.
+    let nan = (0.0, f64::NAN);
+    for objective in [&min, &max].iter() {
+        assert_eq!(Ordering::Greater, objective.total_order(&nan, &a));
+        assert_eq!(Ordering::Less, objective.total_order(&a, &nan));
+        assert_eq!(Ordering::Equal, objective.total_order(&nan, &nan));
+        assert!(!objective.is_valid(&nan));
+        assert!(objective.is_valid(&a));
+    }
+
+    // A maximized NaN value is ranked last, too.
+    let mo = VectorMultiObjective::<(f64, f64)>::maximize(2);
+    let solutions = vec![(1.0, f64::NAN), (1.0, 1.0), (2.0, 0.0)];
+    let selected = SelectNSGA::default()
+        .try_select_and_rank(&solutions, 2, &mo, NanPolicy::Worst)
+        .unwrap();
+    let mut indices: Vec<_> = selected.iter().map(|s| s.index).collect();
+    indices.sort();
+    assert_eq!(vec![1, 2], indices);
+}
+
+#[test]
+fn test_vector_multi_objective() {
+    use multi_objective::objective_vector;
+
+    let mo = VectorMultiObjective::<[f64; 3]>::minimize(3);
+    assert_eq!(3, mo.num_objectives());
+    assert!(mo.is_pareto_dominance());
+    assert_eq!(Ordering::Less, mo.domination_ord(&[1.0, 2.0, 3.0], &[1.0, 2.0, 4.0]));
+    assert_eq!(Ordering::Equal, mo.domination_ord(&[1.0, 2.0, 3.0], &[0.0, 2.0, 4.0]));
+
+    // minimize the first, maximize the second component
+    let mo = VectorMultiObjective::<Vec<f64>>::new(&[Direction::Minimize, Direction::Maximize]);
+    assert_eq!(Ordering::Less, mo.domination_ord(&vec![1.0, 2.0], &vec![1.0, 1.0]));
+    assert_eq!(Ordering::Equal, mo.domination_ord(&vec![1.0, 2.0], &vec![0.0, 1.0]));
+    assert_eq!(vec![-1.0, -1.0], objective_vector(&mo, &vec![1.0, 2.0], &vec![2.0, 1.0]));
+}